@@ -0,0 +1,122 @@
+use serde::Serialize;
+
+use crate::profile::{GameProfile, PROFILES, SF6};
+
+pub const PFB_MAGIC: u32 = u32::from_le_bytes(*b"PFB\0");
+pub const USR_MAGIC: u32 = u32::from_le_bytes(*b"USR\0");
+pub const SCN_MAGIC: u32 = u32::from_le_bytes(*b"SCN\0");
+pub const RSZ_MAGIC: u32 = u32::from_le_bytes(*b"RSZ\0");
+//fchar files start with their version, the magic follows it
+pub const FCHAR_MAGIC: u32 = u32::from_le_bytes(*b"CHAR");
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FileKind {
+    CharacterAsset,
+    Prefab,
+    UserData,
+    Scene,
+    Rsz,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FileType {
+    pub kind: FileKind,
+    //the number the game puts at the end of the file name, e.g. 17 for .pfb.17, or the header version of a bare RSZ
+    pub version: u32,
+    //version of the main RSZ block, which tells titles sharing a file version apart
+    pub rsz_version: Option<u32>,
+}
+
+impl FileType {
+    //The RSZ version decides first, then the file version, unknown ones get the SF6 profile
+    pub fn profile(&self) -> &'static GameProfile {
+        self.rsz_version
+            .and_then(rsz_profile)
+            .or_else(|| supported_profiles().find(|profile| profile.file_version(self.kind) == Some(self.version)))
+            .unwrap_or(&SF6)
+    }
+}
+
+//Only profiles with a bundled schema can be parsed, so the others are never picked
+fn supported_profiles() -> impl Iterator<Item = &'static GameProfile> {
+    PROFILES.iter().filter(|profile| profile.schema.is_some())
+}
+
+fn rsz_profile(rsz_version: u32) -> Option<&'static GameProfile> {
+    supported_profiles().find(|profile| profile.rsz_version == rsz_version)
+}
+
+fn read_u32(input: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(input.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn read_u64(input: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(input.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+//The GameObjectRef table follows the GameObject infos directly, which only lines up with one of the header layouts
+fn prefab_version(input: &[u8]) -> Option<u32> {
+    let info_count = read_u32(input, 4)? as u64;
+    let infos_size = info_count * 12;
+    let matches_17 = read_u64(input, 24) == Some(56 + infos_size);
+    let matches_16 = read_u64(input, 16) == Some(40 + infos_size);
+    match (matches_17, matches_16) {
+        (true, false) => Some(17),
+        (false, true) => Some(16),
+        _ => None,
+    }
+}
+
+//Follows the data offset stored in the header at `data_offset_at` to the version of the main RSZ block
+fn rsz_version(input: &[u8], data_offset_at: usize) -> Option<u32> {
+    let data_offset = read_u64(input, data_offset_at)? as usize;
+    match read_u32(input, data_offset)? {
+        RSZ_MAGIC => read_u32(input, data_offset + 4),
+        _ => None,
+    }
+}
+
+fn extension_version(file_name: &str) -> Option<u32> {
+    file_name.rsplit('.').next()?.parse().ok()
+}
+
+fn extension_kind(file_name: &str) -> Option<FileKind> {
+    let file_name = file_name.to_lowercase();
+    let kinds = [
+        (".fchar.", FileKind::CharacterAsset),
+        (".pfb.", FileKind::Prefab),
+        (".user.", FileKind::UserData),
+        (".scn.", FileKind::Scene),
+    ];
+    kinds.iter()
+        .find(|(extension, _)| file_name.contains(extension))
+        .map(|(_, kind)| *kind)
+}
+
+//Versions come from the header or the embedded RSZ block, the file name is only used when neither has one
+pub fn detect(input: &[u8], file_name: &str) -> Option<FileType> {
+    let (kind, version, rsz_version) = match read_u32(input, 0) {
+        Some(PFB_MAGIC) => {
+            let version = prefab_version(input);
+            let data_offset_at = match version {
+                Some(16) => 32,
+                _ => 48,
+            };
+            (FileKind::Prefab, version, rsz_version(input, data_offset_at))
+        }
+        Some(USR_MAGIC) => (FileKind::UserData, None, rsz_version(input, 32)),
+        Some(SCN_MAGIC) => (FileKind::Scene, None, rsz_version(input, 56)),
+        Some(RSZ_MAGIC) => (FileKind::Rsz, read_u32(input, 4), read_u32(input, 4)),
+        version if read_u32(input, 4) == Some(FCHAR_MAGIC) => (FileKind::CharacterAsset, version, None),
+        _ => (extension_kind(file_name)?, None, None),
+    };
+    //.user and .scn headers carry no version of their own, the game the RSZ block belongs to gives it
+    let version = version
+        .or_else(|| rsz_profile(rsz_version?)?.file_version(kind))
+        .or_else(|| extension_version(file_name));
+    Some(FileType {
+        kind,
+        version: version.unwrap_or(0),
+        rsz_version,
+    })
+}
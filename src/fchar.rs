@@ -5,10 +5,31 @@
 };
 use nom::multi::count;
 use nom::number::complete::{le_i32, le_u32, le_u64};
+use std::collections::HashMap;
+use std::io::Write;
 use serde::{Deserialize, Serialize};
 use num_derive::FromPrimitive;
 
-use crate::rsz::{parse_rsz, RSZ};
+use crate::profile::GameProfile;
+use crate::rsz::{align_to, align_to_16, parse_rsz, read_utf16, verify_error, write_rsz, write_utf16, RSZ, RSZData, RSZValue};
+
+pub mod attack;
+pub mod camera;
+pub mod cancel;
+pub mod combo;
+pub mod command;
+pub mod csv;
+pub mod curve;
+pub mod diff;
+pub mod edit;
+mod fields;
+pub mod personal;
+pub mod projectile;
+pub mod style;
+pub mod timeline;
+pub mod voice;
+
+pub use fields::missing_schema_fields;
 
 #[derive(Serialize, Deserialize)]
 pub struct CharacterAssetHeader {
@@ -114,15 +135,16 @@ pub struct ActionListTable {
 
 fn parse_action_list_table(input: &[u8], offset: usize, style_count: u32) -> IResult<&[u8], ActionListTable>
 {
-    let remainder = &input[offset..];
-    let (remainder, action_list_table_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-    let (_, style_data_offset) = count(le_u64::<&[u8], nom::error::Error<&[u8]>>, style_count as usize - 1)(remainder).unwrap();
-    let remainder = &input[action_list_table_offset as usize..];
-    let (remainder, action_list_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-    let (remainder, action_rsz) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-    let (remainder, data_id_table_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-    let (remainder, action_list_count) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-    let (remainder, object_count) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+    let remainder = input.get(offset..).ok_or_else(|| verify_error(input))?;
+    let (remainder, action_list_table_offset) = le_u64(remainder)?;
+    //the default style is stored separately, so only the extra styles have an offset here
+    let (_, style_data_offset) = count(le_u64, style_count.saturating_sub(1) as usize)(remainder)?;
+    let remainder = input.get(action_list_table_offset as usize..).ok_or_else(|| verify_error(input))?;
+    let (remainder, action_list_offset) = le_u64(remainder)?;
+    let (remainder, action_rsz) = le_u64(remainder)?;
+    let (remainder, data_id_table_offset) = le_u64(remainder)?;
+    let (remainder, action_list_count) = le_u32(remainder)?;
+    let (remainder, object_count) = le_u32(remainder)?;
     return Ok((remainder, ActionListTable {
         action_list_table_offset,
         style_data_offset,
@@ -146,11 +168,11 @@ pub struct StyleData {
 }
 
 fn parse_style_data(input: &[u8], offset: usize) -> IResult<&[u8], StyleData> {
-    let remainder = &input[offset..];
-    let (remainder, data_start_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-    let (remainder, rsz_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-    let (remainder, data_end_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-    let (_, rsz) = parse_rsz(input, rsz_offset as usize).unwrap();
+    let remainder = input.get(offset..).ok_or_else(|| verify_error(input))?;
+    let (remainder, data_start_offset) = le_u64(remainder)?;
+    let (remainder, rsz_offset) = le_u64(remainder)?;
+    let (remainder, data_end_offset) = le_u64(remainder)?;
+    let (_, rsz) = parse_rsz(input, rsz_offset as usize)?;
     return Ok((remainder, StyleData{
         data_start_offset,
         rsz_offset,
@@ -159,7 +181,7 @@ fn parse_style_data(input: &[u8], offset: usize) -> IResult<&[u8], StyleData> {
     }))
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ActionData {
     pub action_id: i32,
     pub frames: i32,
@@ -192,7 +214,7 @@ fn parse_action_data(input: &[u8]) -> IResult<&[u8], ActionData>
     )(input)
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct KeyData {
     pub key_start_frame: i32,
     pub key_end_frame: i32,
@@ -217,7 +239,7 @@ fn parse_key_data(input: &[u8]) -> IResult<&[u8], KeyData>
     )(input)
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ObjectData {
     pub data_count: i32,
     pub reserved: i32,
@@ -226,9 +248,9 @@ pub struct ObjectData {
 
 fn parse_object_data(input: &[u8]) -> IResult<&[u8], ObjectData>
 {
-    let (remainder, data_count) = le_i32::<&[u8], nom::error::Error<&[u8]>>(input).unwrap();
-    let (remainder, reserved) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-    let (remainder, key_data) = count(parse_key_data, data_count as usize)(remainder).unwrap();
+    let (remainder, data_count) = le_i32::<&[u8], nom::error::Error<&[u8]>>(input)?;
+    let (remainder, reserved) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+    let (remainder, key_data) = count(parse_key_data, data_count as usize)(remainder)?;
     Ok((remainder, ObjectData {
         data_count,
         reserved,
@@ -236,7 +258,7 @@ fn parse_object_data(input: &[u8]) -> IResult<&[u8], ObjectData>
     }))
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ActionListInfo {
     #[serde(skip)]
     pub action_offset: u64,
@@ -254,15 +276,15 @@ pub struct ActionListInfo {
 }
 
 fn parse_action_list_info(input: &[u8], offset: usize) -> IResult<&[u8], ActionListInfo> {
-    let remainder = &input[offset..];
-    let (remainder, action_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-    let action_start = &input[action_offset as usize..];
-    let (remainder_new, data_start_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(action_start).unwrap();
-    let (remainder_new, rsz_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder_new).unwrap();
-    let (remainder_new, rsz_end) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder_new).unwrap();
-    let (remainder_new, action_count) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder_new).unwrap();
-    let (remainder_new, object_count) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder_new).unwrap();
-    let (_, action_data) = parse_action_data(remainder_new).unwrap();
+    let remainder = input.get(offset..).ok_or_else(|| verify_error(input))?;
+    let (remainder, action_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+    let action_start = input.get(action_offset as usize..).ok_or_else(|| verify_error(input))?;
+    let (remainder_new, data_start_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(action_start)?;
+    let (remainder_new, rsz_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder_new)?;
+    let (remainder_new, rsz_end) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder_new)?;
+    let (remainder_new, action_count) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder_new)?;
+    let (remainder_new, object_count) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder_new)?;
+    let (_, action_data) = parse_action_data(remainder_new)?;
     return Ok((remainder, ActionListInfo{
         action_offset,
         data_start_offset,
@@ -274,7 +296,7 @@ fn parse_action_list_info(input: &[u8], offset: usize) -> IResult<&[u8], ActionL
     }))
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ObjectInfo {
     #[serde(skip)]
     pub object_offset: u64,
@@ -288,13 +310,13 @@ pub struct ObjectInfo {
 }
 
 fn parse_object_info(input: &[u8], offset: usize) -> IResult<&[u8], ObjectInfo> {
-    let remainder = &input[offset..];
-    let (remainder, object_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-    let object_start = &input[object_offset as usize..];
-    let (remainder_new, data_start_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(object_start).unwrap();
-    let (remainder_new, rsz_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder_new).unwrap();
-    let (remainder_new, rsz_end) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder_new).unwrap();
-    let (_, object_data) = parse_object_data(remainder_new).unwrap();
+    let remainder = input.get(offset..).ok_or_else(|| verify_error(input))?;
+    let (remainder, object_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+    let object_start = input.get(object_offset as usize..).ok_or_else(|| verify_error(input))?;
+    let (remainder_new, data_start_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(object_start)?;
+    let (remainder_new, rsz_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder_new)?;
+    let (remainder_new, rsz_end) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder_new)?;
+    let (_, object_data) = parse_object_data(remainder_new)?;
 
     Ok((remainder, ObjectInfo{
         object_offset,
@@ -305,15 +327,15 @@ fn parse_object_info(input: &[u8], offset: usize) -> IResult<&[u8], ObjectInfo>
     }))
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Object {
     pub info: ObjectInfo,
     pub action: RSZ,
 }
 
 fn parse_object(input: &[u8], offset: usize) -> IResult<&[u8], Object> {
-    let (remainder_new, info) = parse_object_info(input, offset).unwrap();
-    let (_, action) = parse_rsz(input, info.rsz_offset.clone() as usize).unwrap();
+    let (remainder_new, info) = parse_object_info(input, offset)?;
+    let (_, action) = parse_rsz(input, info.rsz_offset as usize)?;
 
     Ok((remainder_new, Object{
         info,
@@ -321,7 +343,7 @@ fn parse_object(input: &[u8], offset: usize) -> IResult<&[u8], Object> {
     }))
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ActionList {
     pub info: ActionListInfo,
     pub action: RSZ,
@@ -329,12 +351,12 @@ pub struct ActionList {
 }
 
 fn parse_action_list(input: &[u8], offset: usize) -> IResult<&[u8], ActionList> {
-    let (_, info) = parse_action_list_info(input, offset).unwrap();
-    let (remainder_new, action) = parse_rsz(input, info.rsz_offset.clone() as usize).unwrap();
+    let (_, info) = parse_action_list_info(input, offset)?;
+    let (remainder_new, action) = parse_rsz(input, info.rsz_offset as usize)?;
     let mut objects: Vec<Object> = vec![];
     for n in 0..info.object_count.clone() {
-        let offset = (info.data_start_offset.clone() + 8 * n as u64) as usize;
-        let (_, object) = parse_object(input, offset).unwrap();
+        let offset = info.data_start_offset.saturating_add(8 * n as u64) as usize;
+        let (_, object) = parse_object(input, offset)?;
         objects.push(object);
     };
     Ok((remainder_new, ActionList{
@@ -386,13 +408,33 @@ pub struct DataListItem {
     pub data_rsz: RSZ,
 }
 
+impl DataListItem {
+    //Each data id is described by the root instance at the same index, which only holds while the counts match
+    pub fn entry_instance(&self, data_id: u32) -> Option<i32> {
+        if self.data_ids.len() != self.data_rsz.object_table.len() {
+            return None;
+        }
+        let index = self.data_ids.iter().position(|id| *id == data_id)?;
+        self.data_rsz.object_table.get(index).copied()
+    }
+
+    pub fn entry(&self, data_id: u32) -> Option<&RSZData> {
+        self.data_rsz.instance_data(self.entry_instance(data_id)?)
+    }
+
+    pub fn entry_mut(&mut self, data_id: u32) -> Option<&mut RSZData> {
+        let instance_id = self.entry_instance(data_id)?;
+        self.data_rsz.instance_data_mut(instance_id)
+    }
+}
+
 fn parse_data_list_item(input: &[u8], offset: usize) -> IResult<&[u8], DataListItem> {
-    let remainder = &input[offset..];
-    let (remainder, data_list_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-    let data_remainder = &input[data_list_offset as usize..];
-    let (data_remainder, info) = parse_data_list_info(data_remainder).unwrap();
-    let (_, data_ids) = count(le_u32::<&[u8], nom::error::Error<&[u8]>>, info.data_count as usize)(data_remainder).unwrap();
-    let (_, data_rsz) = parse_rsz(input, info.rsz_offset.clone() as usize).unwrap();
+    let remainder = input.get(offset..).ok_or_else(|| verify_error(input))?;
+    let (remainder, data_list_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+    let data_remainder = input.get(data_list_offset as usize..).ok_or_else(|| verify_error(input))?;
+    let (data_remainder, info) = parse_data_list_info(data_remainder)?;
+    let (_, data_ids) = count(le_u32::<&[u8], nom::error::Error<&[u8]>>, info.data_count as usize)(data_remainder)?;
+    let (_, data_rsz) = parse_rsz(input, info.rsz_offset as usize)?;
     Ok((remainder, DataListItem{
         data_list_offset,
         info,
@@ -401,7 +443,7 @@ fn parse_data_list_item(input: &[u8], offset: usize) -> IResult<&[u8], DataListI
     }))
 }
 
-#[derive(Serialize, Deserialize, FromPrimitive, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize, FromPrimitive, PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub enum DataId {
     AttackDataParams = 0,
     ChargeParamSub = 1,
@@ -438,12 +480,91 @@ pub enum DataId {
     RectCommon = 106,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct StringInfo {
+    #[serde(skip)]
+    pub string_offset: u64,
+    pub string: String,
+    pub action_ids: Vec<i32>,
+}
+
+//Entries running past the end of the file or pointing outside of it fail like the style table checks do
+fn parse_string_info(input: &[u8], offset: usize) -> IResult<&[u8], StringInfo> {
+    let remainder = input.get(offset..).ok_or_else(|| verify_error(input))?;
+    let (remainder, string_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder)
+        .map_err(|_| verify_error(input))?;
+    let string = read_utf16(input.get(string_offset as usize..).ok_or_else(|| verify_error(input))?);
+    Ok((remainder, StringInfo {
+        string_offset,
+        string,
+        action_ids: vec![],
+    }))
+}
+
+fn collect_rsz_strings<'a>(value: &'a RSZValue, strings: &mut Vec<&'a str>) {
+    match value {
+        RSZValue::String(string) => strings.push(string),
+        RSZValue::List(values) => {
+            for value in values {
+                collect_rsz_strings(value, strings);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn link_string_table(string_table: &mut [StringInfo], action_list: &[ActionList]) {
+    //every string an action uses, mapped to the ids of the actions using it
+    let mut string_actions: HashMap<&str, Vec<i32>> = HashMap::new();
+    for action in action_list {
+        let action_id = action.info.action_data.action_id;
+        let mut strings: Vec<&str> = vec![];
+        let rsz_list = std::iter::once(&action.action).chain(action.objects.iter().map(|object| &object.action));
+        for rsz in rsz_list {
+            for data in &rsz.data {
+                for field in &data.fields {
+                    collect_rsz_strings(&field.value, &mut strings);
+                }
+            }
+        }
+        for string in strings {
+            let action_ids = string_actions.entry(string).or_default();
+            if !action_ids.contains(&action_id) {
+                action_ids.push(action_id);
+            }
+        }
+    }
+    for string_info in string_table.iter_mut() {
+        if let Some(action_ids) = string_actions.get(string_info.string.as_str()) {
+            string_info.action_ids = action_ids.clone();
+        }
+    }
+}
+
+/// Writes the string object table followed by the UTF-16 string pool, with `base_offset` being
+/// the position of the table in the rebuilt file. Returns the pool offset alongside the bytes.
+pub fn write_string_table(string_table: &[StringInfo], base_offset: u64) -> (u64, Vec<u8>) {
+    let mut pool: Vec<u8> = vec![];
+    let mut string_offsets: Vec<usize> = vec![];
+    for string_info in string_table {
+        string_offsets.push(pool.len());
+        write_utf16(&string_info.string, &mut pool);
+    }
+    let table_size = 8 * string_table.len() as u64;
+    let string_offset = base_offset + table_size + (16 - (base_offset + table_size) % 16) % 16;
+    let mut bytes: Vec<u8> = vec![];
+    for offset in string_offsets {
+        bytes.write_all(&(string_offset + offset as u64).to_le_bytes()).unwrap();
+    }
+    bytes.resize((string_offset - base_offset) as usize, 0);
+    bytes.append(&mut pool);
+    (string_offset, bytes)
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct CharacterAsset {
     pub header: CharacterAssetHeader,
-    #[serde(skip)]
     pub id_table: Vec<i32>,
-    #[serde(skip)]
     pub parent_id_table: Vec<i32>,
     #[serde(skip)]
     pub action_list_table: ActionListTable,
@@ -453,60 +574,92 @@ pub struct CharacterAsset {
     pub data_id_table: Vec<DataId>,
     pub data_list_table: Vec<DataListItem>,
     pub personal_data: RSZ,
+    pub string_table: Vec<StringInfo>,
 }
 
-pub fn parse_fchar(input: &[u8]) -> IResult<&[u8], CharacterAsset> {
-    println!("Parsing fchar file...");
-    let (remainder, header) = parse_fchar_header(input).unwrap();
-    let (remainder, id_table) = count(le_i32::<&[u8], nom::error::Error<&[u8]>>, header.style_count as usize)(remainder).unwrap();
-    let (mut remainder, parent_id_table) = count(le_i32::<&[u8], nom::error::Error<&[u8]>>, header.style_count as usize)(remainder).unwrap();
-    let alignment_remainder = (16 - (input.len() - remainder.len()) % 16) % 16;
+//Checks that the style tables and every style offset fit in the file before anything is read from them
+fn validate_style_count(input: &[u8], header: &CharacterAssetHeader, table_offset: usize) -> bool {
+    let style_count = header.style_count as usize;
+    let style_tables_end = table_offset + 8 * style_count;
+    let alignment_remainder = (16 - style_tables_end % 16) % 16;
+    let action_list_table_end = style_tables_end + alignment_remainder + 8 * style_count.max(1);
+    action_list_table_end <= input.len()
+}
+
+impl CharacterAsset {
+    pub fn data_table(&self, data_id: DataId) -> Option<&DataListItem> {
+        let index = self.data_id_table.iter().position(|id| *id == data_id)?;
+        self.data_list_table.get(index)
+    }
+
+    pub fn data_table_mut(&mut self, data_id: DataId) -> Option<&mut DataListItem> {
+        let index = self.data_id_table.iter().position(|id| *id == data_id)?;
+        self.data_list_table.get_mut(index)
+    }
+}
+
+pub fn parse_fchar<'a>(input: &'a [u8], profile: &GameProfile) -> IResult<&'a [u8], CharacterAsset> {
+    let (remainder, header) = parse_fchar_header(input).map_err(|_| verify_error(input))?;
+    if profile.fchar_version != Some(header.version) {
+        return Err(verify_error(input));
+    }
+    if !validate_style_count(input, &header, input.len() - remainder.len()) {
+        return Err(verify_error(input));
+    }
+    let (remainder, id_table) = count(le_i32::<&[u8], nom::error::Error<&[u8]>>, header.style_count as usize)(remainder)
+        .map_err(|_| verify_error(input))?;
+    let (mut remainder, parent_id_table) = count(le_i32::<&[u8], nom::error::Error<&[u8]>>, header.style_count as usize)(remainder)
+        .map_err(|_| verify_error(input))?;
+    let alignment = profile.table_alignment;
+    let alignment_remainder = (alignment - (input.len() - remainder.len()) % alignment) % alignment;
     if alignment_remainder != 0 {
-        remainder = &remainder[alignment_remainder..];
+        remainder = remainder.get(alignment_remainder..).ok_or_else(|| verify_error(input))?;
     }
     let offset = input.len() - remainder.len();
-    let (mut remainder, action_list_table) = parse_action_list_table(input, offset, header.style_count).unwrap();
-    println!("Header parsed!");
-    println!("Parsing style data...");
-    let (_, default_style_data) = parse_rsz(input, action_list_table.action_rsz as usize).unwrap();
+    let (mut remainder, action_list_table) = parse_action_list_table(input, offset, header.style_count)?;
+    let default_style_data = match header.style_count {
+        0 => RSZ::default(),
+        _ => parse_rsz(input, action_list_table.action_rsz as usize)?.1,
+    };
     let mut style_data: Vec<StyleData> = vec![];
-    for n in 0..header.style_count - 1 {
-        let (_, style_data_inst) = parse_style_data(input, action_list_table.style_data_offset[n as usize] as usize).unwrap();
+    for style_data_offset in &action_list_table.style_data_offset {
+        let (_, style_data_inst) = parse_style_data(input, *style_data_offset as usize)?;
         style_data.push(style_data_inst);
     }
-    println!("Style data parsed!");
     let mut action_list: Vec<ActionList> = vec![];
-    println!("Parsing action list...");
     for _ in 0..action_list_table.action_list_count {
         let offset = input.len() - remainder.len();
-        let (_, action) = parse_action_list(input, offset).unwrap();
+        let (_, action) = parse_action_list(input, offset)?;
         action_list.push(action);
-        remainder = &remainder[8..];
+        remainder = remainder.get(8..).ok_or_else(|| verify_error(input))?;
     }
-    println!("Action list parsed!");
 
-    println!("Parsing data tables...");
-    let data_id_remainder = &input[header.data_id_table_offset.clone() as usize..];
-    let (_, data_id_u32_table) = count(le_u32::<&[u8], nom::error::Error<&[u8]>>, header.data_count as usize)(data_id_remainder).unwrap();
+    let data_id_remainder = input.get(header.data_id_table_offset as usize..).ok_or_else(|| verify_error(input))?;
+    let (_, data_id_u32_table) = count(le_u32::<&[u8], nom::error::Error<&[u8]>>, header.data_count as usize)(data_id_remainder)?;
     let mut data_id_table: Vec<DataId> = vec![];
     for data_id in data_id_u32_table {
-        data_id_table.push(num::FromPrimitive::from_u32(data_id).unwrap());
+        data_id_table.push(num::FromPrimitive::from_u32(data_id).ok_or_else(|| verify_error(input))?);
     }
-    let mut data_list_remainder = &input[header.data_list_table_offset.clone() as usize..];
+    let mut data_list_remainder = input.get(header.data_list_table_offset as usize..).ok_or_else(|| verify_error(input))?;
     let mut data_list_table: Vec<DataListItem> = vec![];
     for _ in 0..header.data_count {
         let offset = input.len() - data_list_remainder.len();
-        let (remainder_new, data_list_item) = parse_data_list_item(input, offset).unwrap();
+        let (remainder_new, data_list_item) = parse_data_list_item(input, offset)?;
         data_list_remainder = remainder_new;
         data_list_table.push(data_list_item);
     }
-    println!("Data tables parsed!");
 
-    println!("Parsing personal data...");
-    let (_, personal_data) = parse_rsz(input, header.object_table_rsz_offset.clone() as usize).unwrap();
-    println!("Personal data parsed!");
+    let (_, personal_data) = parse_rsz(input, header.object_table_rsz_offset as usize)?;
 
-    println!("Fchar file parsed!");
+    let mut string_remainder = input.get(header.string_object_offset as usize..).ok_or_else(|| verify_error(input))?;
+    let mut string_table: Vec<StringInfo> = vec![];
+    for _ in 0..header.string_count {
+        let offset = input.len() - string_remainder.len();
+        let (remainder_new, string_info) = parse_string_info(input, offset)?;
+        string_remainder = remainder_new;
+        string_table.push(string_info);
+    }
+    link_string_table(&mut string_table, &action_list);
 
     Ok((input, CharacterAsset {
         header,
@@ -519,5 +672,178 @@ pub fn parse_fchar(input: &[u8]) -> IResult<&[u8], CharacterAsset> {
         data_id_table,
         data_list_table,
         personal_data,
+        string_table,
     }))
-}
\ No newline at end of file
+}
+//Overwrites a table entry reserved earlier, once what it points at has been written
+fn write_at(bytes: &mut [u8], offset: usize, value: &[u8]) {
+    bytes[offset..offset + value.len()].copy_from_slice(value);
+}
+
+fn write_action(action: &ActionList, alignment: usize, bytes: &mut Vec<u8>) -> usize {
+    align_to(bytes, alignment);
+    let action_offset = bytes.len();
+    bytes.resize(action_offset + 48, 0);
+    let object_table = bytes.len();
+    bytes.resize(object_table + 8 * action.objects.len(), 0);
+    align_to_16(bytes);
+    let rsz_offset = bytes.len() as u64;
+    write_rsz(&action.action, bytes);
+    let rsz_end = bytes.len() as u64;
+
+    let mut info: Vec<u8> = vec![];
+    info.write_all(&(object_table as u64).to_le_bytes()).unwrap();
+    info.write_all(&rsz_offset.to_le_bytes()).unwrap();
+    info.write_all(&rsz_end.to_le_bytes()).unwrap();
+    info.write_all(&action.info.action_count.to_le_bytes()).unwrap();
+    info.write_all(&(action.objects.len() as u32).to_le_bytes()).unwrap();
+    let action_data = &action.info.action_data;
+    for value in [action_data.action_id, action_data.frames, action_data.key_start_frame, action_data.key_end_frame] {
+        info.write_all(&value.to_le_bytes()).unwrap();
+    }
+    write_at(bytes, action_offset, &info);
+
+    for (n, object) in action.objects.iter().enumerate() {
+        align_to(bytes, alignment);
+        let object_offset = bytes.len();
+        write_at(bytes, object_table + 8 * n, &(object_offset as u64).to_le_bytes());
+        bytes.resize(object_offset + 24, 0);
+        let object_data = &object.info.object_data;
+        bytes.write_all(&(object_data.key_data.len() as i32).to_le_bytes()).unwrap();
+        bytes.write_all(&object_data.reserved.to_le_bytes()).unwrap();
+        for key_data in &object_data.key_data {
+            bytes.write_all(&key_data.key_start_frame.to_le_bytes()).unwrap();
+            bytes.write_all(&key_data.key_end_frame.to_le_bytes()).unwrap();
+        }
+        align_to_16(bytes);
+        let rsz_offset = bytes.len() as u64;
+        write_rsz(&object.action, bytes);
+        let rsz_end = bytes.len() as u64;
+        write_at(bytes, object_offset, &(object_offset as u64 + 24).to_le_bytes());
+        write_at(bytes, object_offset + 8, &rsz_offset.to_le_bytes());
+        write_at(bytes, object_offset + 16, &rsz_end.to_le_bytes());
+    }
+    action_offset
+}
+
+fn write_data_list(data_list: &DataListItem, alignment: usize, bytes: &mut Vec<u8>) -> usize {
+    align_to(bytes, alignment);
+    let data_list_offset = bytes.len();
+    bytes.resize(data_list_offset + 28, 0);
+    for data_id in &data_list.data_ids {
+        bytes.write_all(&data_id.to_le_bytes()).unwrap();
+    }
+    align_to_16(bytes);
+    let rsz_offset = bytes.len() as u64;
+    write_rsz(&data_list.data_rsz, bytes);
+    let data_end_offset = bytes.len() as u64;
+    write_at(bytes, data_list_offset, &(data_list_offset as u64 + 28).to_le_bytes());
+    write_at(bytes, data_list_offset + 8, &rsz_offset.to_le_bytes());
+    write_at(bytes, data_list_offset + 16, &data_end_offset.to_le_bytes());
+    write_at(bytes, data_list_offset + 24, &(data_list.data_ids.len() as u32).to_le_bytes());
+    data_list_offset
+}
+
+//Tables come first in the order they are parsed, every block with an RSZ follows them. Counts come from the
+//tables themselves and the id table decides the style count; offsets no parser follows point past each entry
+pub fn write_fchar(fchar: &CharacterAsset, profile: &GameProfile) -> Vec<u8> {
+    let alignment = profile.table_alignment;
+    let style_count = fchar.id_table.len();
+    let mut bytes: Vec<u8> = vec![0; 96];
+    let id_table_offset = bytes.len() as u64;
+    for id in &fchar.id_table {
+        bytes.write_all(&id.to_le_bytes()).unwrap();
+    }
+    let parent_id_table_offset = bytes.len() as u64;
+    for n in 0..style_count {
+        bytes.write_all(&fchar.parent_id_table.get(n).copied().unwrap_or(-1).to_le_bytes()).unwrap();
+    }
+    align_to(&mut bytes, alignment);
+    //the action list table offset is followed by one offset per style after the default one
+    let style_table = bytes.len();
+    bytes.resize(style_table + 8 * style_count.max(1), 0);
+    let action_list_table_offset = bytes.len();
+    bytes.resize(action_list_table_offset + 32, 0);
+    let action_list_offset = bytes.len();
+    bytes.resize(action_list_offset + 8 * fchar.action_list.len(), 0);
+    align_to(&mut bytes, alignment);
+    let data_id_table_offset = bytes.len() as u64;
+    for data_id in &fchar.data_id_table {
+        bytes.write_all(&(*data_id as u32).to_le_bytes()).unwrap();
+    }
+    align_to(&mut bytes, alignment);
+    let data_list_table_offset = bytes.len();
+    bytes.resize(data_list_table_offset + 8 * fchar.data_list_table.len(), 0);
+    align_to(&mut bytes, alignment);
+    let string_object_offset = bytes.len() as u64;
+    let (string_offset, mut string_bytes) = write_string_table(&fchar.string_table, string_object_offset);
+    bytes.append(&mut string_bytes);
+
+    let action_rsz = match style_count {
+        0 => 0,
+        _ => {
+            align_to_16(&mut bytes);
+            let action_rsz = bytes.len() as u64;
+            write_rsz(&fchar.default_style_data, &mut bytes);
+            action_rsz
+        }
+    };
+    for (n, style) in fchar.style_data.iter().take(style_count.saturating_sub(1)).enumerate() {
+        align_to(&mut bytes, alignment);
+        let style_offset = bytes.len();
+        write_at(&mut bytes, style_table + 8 * (n + 1), &(style_offset as u64).to_le_bytes());
+        bytes.resize(style_offset + 24, 0);
+        align_to_16(&mut bytes);
+        let rsz_offset = bytes.len() as u64;
+        write_rsz(&style.rsz, &mut bytes);
+        let data_end_offset = bytes.len() as u64;
+        write_at(&mut bytes, style_offset, &(style_offset as u64 + 24).to_le_bytes());
+        write_at(&mut bytes, style_offset + 8, &rsz_offset.to_le_bytes());
+        write_at(&mut bytes, style_offset + 16, &data_end_offset.to_le_bytes());
+    }
+    for (n, action) in fchar.action_list.iter().enumerate() {
+        let action_offset = write_action(action, alignment, &mut bytes);
+        write_at(&mut bytes, action_list_offset + 8 * n, &(action_offset as u64).to_le_bytes());
+    }
+    for (n, data_list) in fchar.data_list_table.iter().enumerate() {
+        let data_list_offset = write_data_list(data_list, alignment, &mut bytes);
+        write_at(&mut bytes, data_list_table_offset + 8 * n, &(data_list_offset as u64).to_le_bytes());
+    }
+    align_to_16(&mut bytes);
+    let object_table_rsz_offset = bytes.len() as u64;
+    write_rsz(&fchar.personal_data, &mut bytes);
+    let object_table_rsz_end = bytes.len() as u64;
+
+    let object_count: u32 = fchar.action_list.iter().map(|action| action.objects.len() as u32).sum();
+    let mut action_list_table: Vec<u8> = vec![];
+    action_list_table.write_all(&(action_list_offset as u64).to_le_bytes()).unwrap();
+    action_list_table.write_all(&action_rsz.to_le_bytes()).unwrap();
+    action_list_table.write_all(&data_id_table_offset.to_le_bytes()).unwrap();
+    action_list_table.write_all(&(fchar.action_list.len() as u32).to_le_bytes()).unwrap();
+    action_list_table.write_all(&object_count.to_le_bytes()).unwrap();
+    write_at(&mut bytes, action_list_table_offset, &action_list_table);
+    write_at(&mut bytes, style_table, &(action_list_table_offset as u64).to_le_bytes());
+
+    let mut header: Vec<u8> = vec![];
+    header.write_all(&fchar.header.version.to_le_bytes()).unwrap();
+    header.write_all(&fchar.header.magic.to_le_bytes()).unwrap();
+    for offset in [
+        id_table_offset,
+        parent_id_table_offset,
+        action_list_table_offset as u64,
+        data_id_table_offset,
+        data_list_table_offset as u64,
+        string_object_offset,
+        string_offset,
+        object_table_rsz_offset,
+        object_table_rsz_end,
+    ] {
+        header.write_all(&offset.to_le_bytes()).unwrap();
+    }
+    header.write_all(&fchar.header.object_count.to_le_bytes()).unwrap();
+    header.write_all(&(style_count as u32).to_le_bytes()).unwrap();
+    header.write_all(&(fchar.data_list_table.len() as u32).to_le_bytes()).unwrap();
+    header.write_all(&(fchar.string_table.len() as u32).to_le_bytes()).unwrap();
+    write_at(&mut bytes, 0, &header);
+    bytes
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use serde::Serialize;
+
+use crate::fchar::{CharacterAsset, DataId};
+use crate::fchar::csv::write_csv;
+use crate::fchar::fields::{ATTACK_KEY_FIELD, BLOCK_STUN_FIELD, COMMON_ATTACK_KEY_FIELD, DAMAGE_FIELD, DRIVE_DAMAGE_FIELD, DRIVE_GAIN_FIELD, HIT_STOP_FIELD, HIT_STUN_FIELD, KARMA_ATTACK_KEY_FIELD, STUN_FIELD, SUPER_GAIN_FIELD};
+use crate::rsz::RSZData;
+
+pub const ATTACK_TABLES: [DataId; 3] = [DataId::AttackDataParams, DataId::AttackDataCommon, DataId::AttackDataKarma];
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+pub enum AttackField {
+    Damage,
+    Stun,
+    DriveGain,
+    DriveDamage,
+    SuperGain,
+    HitStop,
+    HitStun,
+    BlockStun,
+}
+
+pub const ATTACK_FIELDS: [AttackField; 8] = [
+    AttackField::Damage,
+    AttackField::Stun,
+    AttackField::DriveGain,
+    AttackField::DriveDamage,
+    AttackField::SuperGain,
+    AttackField::HitStop,
+    AttackField::HitStun,
+    AttackField::BlockStun,
+];
+
+impl AttackField {
+    pub fn field_name(&self) -> &'static str {
+        match self {
+            AttackField::Damage => DAMAGE_FIELD,
+            AttackField::Stun => STUN_FIELD,
+            AttackField::DriveGain => DRIVE_GAIN_FIELD,
+            AttackField::DriveDamage => DRIVE_DAMAGE_FIELD,
+            AttackField::SuperGain => SUPER_GAIN_FIELD,
+            AttackField::HitStop => HIT_STOP_FIELD,
+            AttackField::HitStun => HIT_STUN_FIELD,
+            AttackField::BlockStun => BLOCK_STUN_FIELD,
+        }
+    }
+
+    pub fn column_name(&self) -> &'static str {
+        match self {
+            AttackField::Damage => "damage",
+            AttackField::Stun => "stun",
+            AttackField::DriveGain => "drive_gain",
+            AttackField::DriveDamage => "drive_damage",
+            AttackField::SuperGain => "super_gain",
+            AttackField::HitStop => "hitstop",
+            AttackField::HitStun => "hitstun",
+            AttackField::BlockStun => "blockstun",
+        }
+    }
+}
+
+//Field an action key uses to point at an entry of the given attack table
+fn reference_field(table: DataId) -> &'static str {
+    match table {
+        DataId::AttackDataCommon => COMMON_ATTACK_KEY_FIELD,
+        DataId::AttackDataKarma => KARMA_ATTACK_KEY_FIELD,
+        _ => ATTACK_KEY_FIELD,
+    }
+}
+
+#[derive(Serialize)]
+pub struct AttackData {
+    pub table: DataId,
+    pub id: u32,
+    pub damage: Option<f64>,
+    pub stun: Option<f64>,
+    pub drive_gain: Option<f64>,
+    pub drive_damage: Option<f64>,
+    pub super_gain: Option<f64>,
+    pub hitstop: Option<f64>,
+    pub hitstun: Option<f64>,
+    pub blockstun: Option<f64>,
+    //ids of the actions with a key referencing this entry
+    pub actions: Vec<i32>,
+}
+
+impl AttackData {
+    fn from_rsz(table: DataId, id: u32, data: &RSZData, actions: Vec<i32>) -> AttackData {
+        let value = |field: AttackField| data.get_f64(field.field_name());
+        AttackData {
+            table,
+            id,
+            damage: value(AttackField::Damage),
+            stun: value(AttackField::Stun),
+            drive_gain: value(AttackField::DriveGain),
+            drive_damage: value(AttackField::DriveDamage),
+            super_gain: value(AttackField::SuperGain),
+            hitstop: value(AttackField::HitStop),
+            hitstun: value(AttackField::HitStun),
+            blockstun: value(AttackField::BlockStun),
+            actions,
+        }
+    }
+
+    pub fn get(&self, field: AttackField) -> Option<f64> {
+        match field {
+            AttackField::Damage => self.damage,
+            AttackField::Stun => self.stun,
+            AttackField::DriveGain => self.drive_gain,
+            AttackField::DriveDamage => self.drive_damage,
+            AttackField::SuperGain => self.super_gain,
+            AttackField::HitStop => self.hitstop,
+            AttackField::HitStun => self.hitstun,
+            AttackField::BlockStun => self.blockstun,
+        }
+    }
+}
+
+//Action ids referencing each attack table entry, built once so callers don't walk every action per entry
+pub type AttackReferences = HashMap<(DataId, u32), Vec<i32>>;
+
+impl CharacterAsset {
+    pub fn attack_reference_index(&self) -> AttackReferences {
+        let mut references: AttackReferences = HashMap::new();
+        for action in &self.action_list {
+            let action_id = action.info.action_data.action_id;
+            for key in action.keys() {
+                for table in ATTACK_TABLES {
+                    if let Some(id) = key.data.get_i64(reference_field(table)).filter(|id| *id >= 0) {
+                        let actions = references.entry((table, id as u32)).or_default();
+                        if !actions.contains(&action_id) {
+                            actions.push(action_id);
+                        }
+                    }
+                }
+            }
+        }
+        references
+    }
+
+    pub fn attack_references(&self, table: DataId, id: u32) -> Vec<i32> {
+        let mut actions: Vec<i32> = vec![];
+        for action in &self.action_list {
+            let action_id = action.info.action_data.action_id;
+            let referenced = action.keys().iter()
+                .any(|key| key.data.get_i64(reference_field(table)) == Some(id as i64));
+            if referenced && !actions.contains(&action_id) {
+                actions.push(action_id);
+            }
+        }
+        actions
+    }
+
+    //Attack data of each key of an action that references one, ordered by the key's start frame
+    pub fn action_hits(&self, action_id: i32) -> Vec<(i32, AttackData)> {
+        self.action_hits_with(action_id, &self.attack_reference_index())
+    }
+
+    //Same as action_hits, reusing an index from attack_reference_index across several actions
+    pub fn action_hits_with(&self, action_id: i32, references: &AttackReferences) -> Vec<(i32, AttackData)> {
+        let action = match self.action(action_id) {
+            Some(action) => action,
+            None => return vec![],
+        };
+        let mut hits: Vec<(i32, AttackData)> = vec![];
+        for key in action.keys() {
+            for table in ATTACK_TABLES {
+                let entry = key.data.get_i64(reference_field(table))
+                    .filter(|id| *id >= 0)
+                    .and_then(|id| self.indexed_attack_entry(table, id as u32, references));
+                if let Some(entry) = entry {
+                    hits.push((key.key_data.key_start_frame, entry));
+                    break;
+                }
+            }
+        }
+        hits.sort_by_key(|(frame, _)| *frame);
+        hits
+    }
+
+    pub fn attack_entry(&self, table: DataId, id: u32) -> Option<AttackData> {
+        let data = self.data_table(table)?.entry(id)?;
+        let actions = self.attack_references(table, id);
+        Some(AttackData::from_rsz(table, id, data, actions))
+    }
+
+    fn indexed_attack_entry(&self, table: DataId, id: u32, references: &AttackReferences) -> Option<AttackData> {
+        let data = self.data_table(table)?.entry(id)?;
+        let actions = references.get(&(table, id)).cloned().unwrap_or_default();
+        Some(AttackData::from_rsz(table, id, data, actions))
+    }
+
+    pub fn attack_data(&self) -> Vec<AttackData> {
+        let references = self.attack_reference_index();
+        let mut attack_data: Vec<AttackData> = vec![];
+        for table in ATTACK_TABLES {
+            if let Some(data_list) = self.data_table(table) {
+                for id in &data_list.data_ids {
+                    if let Some(entry) = self.indexed_attack_entry(table, *id, &references) {
+                        attack_data.push(entry);
+                    }
+                }
+            }
+        }
+        attack_data
+    }
+
+    //Returns false if the entry doesn't exist or has no such field
+    pub fn set_attack_value(&mut self, table: DataId, id: u32, field: AttackField, value: f64) -> bool {
+        match self.data_table_mut(table).and_then(|data_list| data_list.entry_mut(id)) {
+            Some(data) => data.set_f64(field.field_name(), value),
+            None => false,
+        }
+    }
+}
+
+pub fn attack_data_to_csv(attack_data: &[AttackData]) -> String {
+    let mut header = vec!["table", "id"];
+    header.extend(ATTACK_FIELDS.iter().map(|field| field.column_name()));
+    header.push("actions");
+    let rows = attack_data.iter().map(|entry| {
+        let mut row = vec![format!("{:?}", entry.table), entry.id.to_string()];
+        row.extend(ATTACK_FIELDS.iter().map(|field| entry.get(*field).map(|value| value.to_string()).unwrap_or_default()));
+        row.push(entry.actions.iter().map(|action| action.to_string()).collect::<Vec<String>>().join(" "));
+        row
+    }).collect();
+    write_csv(&header, &rows)
+}
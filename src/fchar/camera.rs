@@ -0,0 +1,159 @@
+use serde::Serialize;
+
+use crate::fchar::{ActionList, CharacterAsset, DataId};
+use crate::fchar::fields::{CAMERA_BOX_KEY_FIELD, CAMERA_KEY_FIELD, FOV_FIELD, POSITION_FIELD, RECT_FIELD, TARGET_FIELD, VIBRATION_ATTENUATION_FIELD, VIBRATION_FRAME_FIELD, VIBRATION_KEY_FIELD, VIBRATION_POWER_FIELD};
+use crate::rsz::RSZData;
+
+#[derive(Serialize)]
+pub struct CameraKeyframe {
+    pub entry_id: u32,
+    pub frame: i32,
+    pub end_frame: i32,
+    pub position: Vec<f64>,
+    pub target: Vec<f64>,
+    pub fov: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct CameraBoxRange {
+    pub entry_id: u32,
+    pub frame: i32,
+    pub end_frame: i32,
+    pub rect: Vec<f64>,
+}
+
+#[derive(Serialize)]
+pub struct ScreenVibration {
+    pub entry_id: u32,
+    pub frame: i32,
+    pub power: Option<f64>,
+    pub frames: Option<i64>,
+    pub attenuation: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct ActionCamera {
+    pub action_id: i32,
+    pub keyframes: Vec<CameraKeyframe>,
+    pub boxes: Vec<CameraBoxRange>,
+    pub vibrations: Vec<ScreenVibration>,
+}
+
+fn components(data: &RSZData, name: &str) -> Vec<f64> {
+    data.find_field(name)
+        .and_then(|field| field.value.components())
+        .unwrap_or_default()
+}
+
+impl CharacterAsset {
+    //Looks up the table entry an action key points at through the given field
+    fn key_entry(&self, key: &RSZData, field: &str, table: DataId) -> Option<(u32, &RSZData)> {
+        let id = key.get_i64(field).filter(|id| *id >= 0)? as u32;
+        Some((id, self.data_table(table)?.entry(id)?))
+    }
+
+    fn list_camera(&self, action: &ActionList) -> ActionCamera {
+        let mut camera = ActionCamera {
+            action_id: action.info.action_data.action_id,
+            keyframes: vec![],
+            boxes: vec![],
+            vibrations: vec![],
+        };
+        for key in action.keys() {
+            let frame = key.key_data.key_start_frame;
+            let end_frame = key.key_data.key_end_frame;
+            if let Some((entry_id, entry)) = self.key_entry(key.data, CAMERA_KEY_FIELD, DataId::CameraData) {
+                camera.keyframes.push(CameraKeyframe {
+                    entry_id,
+                    frame,
+                    end_frame,
+                    position: components(entry, POSITION_FIELD),
+                    target: components(entry, TARGET_FIELD),
+                    fov: entry.get_f64(FOV_FIELD),
+                });
+            }
+            if let Some((entry_id, entry)) = self.key_entry(key.data, CAMERA_BOX_KEY_FIELD, DataId::CameraBox) {
+                camera.boxes.push(CameraBoxRange {
+                    entry_id,
+                    frame,
+                    end_frame,
+                    rect: components(entry, RECT_FIELD),
+                });
+            }
+            if let Some((entry_id, entry)) = self.key_entry(key.data, VIBRATION_KEY_FIELD, DataId::ScreenVibration) {
+                camera.vibrations.push(ScreenVibration {
+                    entry_id,
+                    frame,
+                    power: entry.get_f64(VIBRATION_POWER_FIELD),
+                    frames: entry.get_i64(VIBRATION_FRAME_FIELD),
+                    attenuation: entry.get_f64(VIBRATION_ATTENUATION_FIELD),
+                });
+            }
+        }
+        camera.keyframes.sort_by_key(|keyframe| keyframe.frame);
+        camera.boxes.sort_by_key(|camera_box| camera_box.frame);
+        camera.vibrations.sort_by_key(|vibration| vibration.frame);
+        camera
+    }
+
+    pub fn action_camera(&self, action_id: i32) -> Option<ActionCamera> {
+        Some(self.list_camera(self.action(action_id)?))
+    }
+
+    //Only actions that use a camera, camera box or screen vibration are listed, every action list entry is visited
+    //even when ids repeat
+    pub fn action_cameras(&self) -> Vec<ActionCamera> {
+        self.action_list.iter()
+            .map(|action| self.list_camera(action))
+            .filter(|camera| !camera.keyframes.is_empty() || !camera.boxes.is_empty() || !camera.vibrations.is_empty())
+            .collect()
+    }
+
+    //Leaves a value untouched when None is passed, returns false if the entry or a field is missing
+    pub fn set_camera_keyframe(&mut self, entry_id: u32, position: Option<&[f64]>, target: Option<&[f64]>, fov: Option<f64>) -> bool {
+        let entry = match self.data_table_mut(DataId::CameraData).and_then(|data_list| data_list.entry_mut(entry_id)) {
+            Some(entry) => entry,
+            None => return false,
+        };
+        let mut set = true;
+        for (name, components) in [(POSITION_FIELD, position), (TARGET_FIELD, target)] {
+            if let Some(components) = components {
+                set &= entry.find_field_mut(name).map(|field| field.value.set_components(components)).unwrap_or(false);
+            }
+        }
+        if let Some(fov) = fov {
+            set &= entry.set_f64(FOV_FIELD, fov);
+        }
+        set
+    }
+
+    pub fn set_camera_box(&mut self, entry_id: u32, rect: &[f64]) -> bool {
+        self.data_table_mut(DataId::CameraBox)
+            .and_then(|data_list| data_list.entry_mut(entry_id))
+            .and_then(|entry| entry.find_field_mut(RECT_FIELD))
+            .map(|field| field.value.set_components(rect))
+            .unwrap_or(false)
+    }
+
+    pub fn set_screen_vibration(&mut self, entry_id: u32, power: Option<f64>, frames: Option<i64>, attenuation: Option<f64>) -> bool {
+        let entry = match self.data_table_mut(DataId::ScreenVibration).and_then(|data_list| data_list.entry_mut(entry_id)) {
+            Some(entry) => entry,
+            None => return false,
+        };
+        let power_set = power.map(|power| entry.set_f64(VIBRATION_POWER_FIELD, power)).unwrap_or(true);
+        let frames_set = frames.map(|frames| entry.set_i64(VIBRATION_FRAME_FIELD, frames)).unwrap_or(true);
+        let attenuation_set = attenuation.map(|attenuation| entry.set_f64(VIBRATION_ATTENUATION_FIELD, attenuation)).unwrap_or(true);
+        power_set && frames_set && attenuation_set
+    }
+
+    //Zeroes the power of every screen vibration, returns how many entries were changed
+    pub fn disable_screen_vibrations(&mut self) -> usize {
+        let entry_ids = match self.data_table(DataId::ScreenVibration) {
+            Some(data_list) => data_list.data_ids.clone(),
+            None => return 0,
+        };
+        entry_ids.iter()
+            .filter(|entry_id| self.set_screen_vibration(**entry_id, Some(0.0), None, None))
+            .count()
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+use serde::Serialize;
+
+use crate::fchar::{CharacterAsset, DataId};
+use crate::fchar::fields::{CONDITION_FIELD, TRIGGER_ACTION_FIELD, TRIGGER_GROUP_FIELD, TRIGGER_KEY_CLASS};
+use crate::rsz::{RSZData, RSZValue};
+
+#[derive(Serialize, Clone)]
+pub struct CancelEdge {
+    pub from: i32,
+    pub to: i32,
+    pub trigger_group: i64,
+    pub trigger_id: u32,
+    pub condition: String,
+    pub start_frame: i32,
+    pub end_frame: i32,
+}
+
+impl CancelEdge {
+    pub fn label(&self) -> String {
+        match self.condition.is_empty() {
+            true => format!("trigger {} [{}-{}]", self.trigger_id, self.start_frame, self.end_frame),
+            false => format!("trigger {} {} [{}-{}]", self.trigger_id, self.condition, self.start_frame, self.end_frame),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CancelGraph {
+    pub nodes: Vec<i32>,
+    pub edges: Vec<CancelEdge>,
+}
+
+//Trigger groups either list their trigger ids or store them as a bitset. The bitset layout is a guess that has not
+//been checked against game data: bytes in order, least significant bit first, bit n meaning trigger id n
+fn group_trigger_ids(group: &RSZData) -> Vec<u32> {
+    let mut trigger_ids: Vec<u32> = vec![];
+    for field in &group.fields {
+        match &field.value {
+            RSZValue::List(values) => {
+                trigger_ids.extend(values.iter().filter_map(|value| value.as_i64()).map(|id| id as u32));
+            }
+            RSZValue::Unk(bytes) => {
+                for (n, byte) in bytes.iter().enumerate() {
+                    for bit in 0..8 {
+                        if byte & (1 << bit) != 0 {
+                            trigger_ids.push((n * 8 + bit) as u32);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    trigger_ids
+}
+
+impl CharacterAsset {
+    pub fn cancel_graph(&self) -> CancelGraph {
+        let trigger_groups = self.data_table(DataId::TriggerGroup);
+        let triggers = self.data_table(DataId::Trigger);
+        let mut nodes: Vec<i32> = vec![];
+        let mut edges: Vec<CancelEdge> = vec![];
+        for action in &self.action_list {
+            let from = action.info.action_data.action_id;
+            nodes.push(from);
+            for key in action.keys() {
+                if !key.data.name.ends_with(TRIGGER_KEY_CLASS) {
+                    continue;
+                }
+                let trigger_group = match key.data.get_i64(TRIGGER_GROUP_FIELD) {
+                    Some(trigger_group) => trigger_group,
+                    None => continue,
+                };
+                let condition = key.data.find_field(CONDITION_FIELD)
+                    .map(|field| field.value.to_string())
+                    .unwrap_or_default();
+                let group = match trigger_groups.and_then(|table| table.entry(trigger_group as u32)) {
+                    Some(group) => group,
+                    None => continue,
+                };
+                for trigger_id in group_trigger_ids(group) {
+                    let to = triggers.and_then(|table| table.entry(trigger_id))
+                        .and_then(|trigger| trigger.get_i64(TRIGGER_ACTION_FIELD));
+                    if let Some(to) = to {
+                        edges.push(CancelEdge {
+                            from,
+                            to: to as i32,
+                            trigger_group,
+                            trigger_id,
+                            condition: condition.clone(),
+                            start_frame: key.key_data.key_start_frame,
+                            end_frame: key.key_data.key_end_frame,
+                        });
+                    }
+                }
+            }
+        }
+        for edge in &edges {
+            if !nodes.contains(&edge.to) {
+                nodes.push(edge.to);
+            }
+        }
+        CancelGraph {
+            nodes,
+            edges,
+        }
+    }
+}
+
+impl CancelGraph {
+    pub fn adjacency(&self) -> BTreeMap<i32, Vec<&CancelEdge>> {
+        let mut adjacency: BTreeMap<i32, Vec<&CancelEdge>> = BTreeMap::new();
+        for node in &self.nodes {
+            adjacency.insert(*node, vec![]);
+        }
+        for edge in &self.edges {
+            adjacency.entry(edge.from).or_default().push(edge);
+        }
+        adjacency
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph cancels {\n");
+        for node in &self.nodes {
+            dot.push_str(&format!("    \"{}\";\n", node));
+        }
+        for edge in &self.edges {
+            dot.push_str(&format!("    \"{}\" -> \"{}\" [label=\"{}\"];\n", edge.from, edge.to, edge.label().replace('"', "\\\"")));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
@@ -0,0 +1,332 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+use crate::fchar::{CharacterAsset, DataId, DataListItem};
+use crate::fchar::fields::{CHARGE_FRAME_FIELD, CHARGE_ID_FIELD, INPUT_LIST_FIELD, KEY_FLAGS_FIELD, WINDOW_FIELD};
+use crate::rsz::json_parser::TypeIDs;
+use crate::rsz::{RSZData, RSZValue};
+
+//The bit layout of the key flags is a guess that hasn't been checked against game data. Each strength bit is a
+//button held in the step, so LP+MP+HP is all three punches at once, while the ANY bits stand for P and K, a single
+//button of any strength
+pub const INPUT_UP: u32 = 1 << 0;
+pub const INPUT_DOWN: u32 = 1 << 1;
+pub const INPUT_FORWARD: u32 = 1 << 2;
+pub const INPUT_BACK: u32 = 1 << 3;
+pub const INPUT_LP: u32 = 1 << 4;
+pub const INPUT_MP: u32 = 1 << 5;
+pub const INPUT_HP: u32 = 1 << 6;
+pub const INPUT_LK: u32 = 1 << 7;
+pub const INPUT_MK: u32 = 1 << 8;
+pub const INPUT_HK: u32 = 1 << 9;
+pub const INPUT_ANY_PUNCH: u32 = 1 << 10;
+pub const INPUT_ANY_KICK: u32 = 1 << 11;
+
+const DIRECTION_MASK: u32 = INPUT_UP | INPUT_DOWN | INPUT_FORWARD | INPUT_BACK;
+
+#[derive(Debug, Clone)]
+pub struct CommandError(pub String);
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Command error: {}", self.0)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct CommandStep {
+    pub key_flags: u32,
+    pub window: i32,
+    //the direction has to be held, e.g. [4]
+    pub charge: bool,
+    //charge steps read from a file reference an entry of the charge table, parsed notation has none
+    pub charge_id: Option<u32>,
+    pub charge_frames: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub struct DecodedCommand {
+    pub id: u32,
+    //community notation, e.g. 236P or [4]6K
+    pub notation: String,
+    //one space separated token per stored step, accepted back by the encoder
+    pub step_notation: String,
+    pub steps: Vec<CommandStep>,
+}
+
+#[derive(Serialize)]
+pub struct DecodedCommandGroup {
+    pub id: u32,
+    pub commands: Vec<u32>,
+    pub notations: Vec<String>,
+}
+
+pub fn direction_to_numpad(key_flags: u32) -> u8 {
+    let vertical = match (key_flags & INPUT_UP != 0, key_flags & INPUT_DOWN != 0) {
+        (true, false) => 6,
+        (false, true) => 0,
+        _ => 3,
+    };
+    let horizontal = match (key_flags & INPUT_BACK != 0, key_flags & INPUT_FORWARD != 0) {
+        (true, false) => 1,
+        (false, true) => 3,
+        _ => 2,
+    };
+    vertical + horizontal
+}
+
+pub fn numpad_to_direction(numpad: u8) -> Option<u32> {
+    let vertical = match numpad {
+        1..=3 => INPUT_DOWN,
+        4..=6 => 0,
+        7..=9 => INPUT_UP,
+        _ => return None,
+    };
+    let horizontal = match numpad % 3 {
+        1 => INPUT_BACK,
+        0 => INPUT_FORWARD,
+        _ => 0,
+    };
+    Some(vertical | horizontal)
+}
+
+const BUTTONS: [(u32, &str); 8] = [
+    (INPUT_LP, "LP"),
+    (INPUT_MP, "MP"),
+    (INPUT_HP, "HP"),
+    (INPUT_ANY_PUNCH, "P"),
+    (INPUT_LK, "LK"),
+    (INPUT_MK, "MK"),
+    (INPUT_HK, "HK"),
+    (INPUT_ANY_KICK, "K"),
+];
+
+//Buttons held together are joined with +
+fn buttons_to_notation(key_flags: u32) -> String {
+    BUTTONS.iter()
+        .filter(|(flag, _)| key_flags & flag != 0)
+        .map(|(_, name)| *name)
+        .collect::<Vec<&str>>()
+        .join("+")
+}
+
+pub fn step_to_notation(step: &CommandStep) -> String {
+    let buttons = buttons_to_notation(step.key_flags);
+    let direction = step.key_flags & DIRECTION_MASK;
+    if step.charge {
+        return format!("[{}]{}", direction_to_numpad(direction), buttons);
+    }
+    match (direction, buttons.is_empty()) {
+        (0, false) => buttons,
+        _ => format!("{}{}", direction_to_numpad(direction), buttons),
+    }
+}
+
+fn parse_buttons(notation: &str) -> (u32, &str) {
+    let mut key_flags = 0u32;
+    let mut remainder = notation;
+    loop {
+        let (flags, length) = match remainder.as_bytes() {
+            [b'L', b'P', ..] => (INPUT_LP, 2),
+            [b'M', b'P', ..] => (INPUT_MP, 2),
+            [b'H', b'P', ..] => (INPUT_HP, 2),
+            [b'L', b'K', ..] => (INPUT_LK, 2),
+            [b'M', b'K', ..] => (INPUT_MK, 2),
+            [b'H', b'K', ..] => (INPUT_HK, 2),
+            [b'P', ..] => (INPUT_ANY_PUNCH, 1),
+            [b'K', ..] => (INPUT_ANY_KICK, 1),
+            [b'+', ..] if key_flags != 0 => (0, 1),
+            _ => break,
+        };
+        key_flags |= flags;
+        remainder = &remainder[length..];
+    }
+    (key_flags, remainder)
+}
+
+//Digits directly followed by buttons form one step, spaces only separate tokens
+pub fn parse_notation(notation: &str) -> Result<Vec<CommandStep>, CommandError> {
+    let mut steps: Vec<CommandStep> = vec![];
+    let mut remainder = notation.trim();
+    while !remainder.is_empty() {
+        let mut step = CommandStep {
+            key_flags: 0,
+            window: 0,
+            charge: false,
+            charge_id: None,
+            charge_frames: None,
+        };
+        let bytes = remainder.as_bytes();
+        let token_length = remainder.len();
+        if bytes[0] == b' ' {
+            remainder = &remainder[1..];
+            continue;
+        }
+        else if bytes[0] == b'[' {
+            if bytes.len() < 3 || bytes[2] != b']' {
+                return Err(CommandError(format!("unterminated charge input in {}", notation)));
+            }
+            step.key_flags = numpad_to_direction(bytes[1].wrapping_sub(b'0'))
+                .ok_or_else(|| CommandError(format!("invalid charge direction in {}", notation)))?;
+            step.charge = true;
+            remainder = &remainder[3..];
+        }
+        else if bytes[0].is_ascii_digit() {
+            step.key_flags = numpad_to_direction(bytes[0] - b'0')
+                .ok_or_else(|| CommandError(format!("invalid direction in {}", notation)))?;
+            remainder = &remainder[1..];
+        }
+        let (buttons, button_remainder) = parse_buttons(remainder);
+        if button_remainder.len() == token_length {
+            return Err(CommandError(format!("unexpected input {} in {}", remainder, notation)));
+        }
+        step.key_flags |= buttons;
+        remainder = button_remainder;
+        steps.push(step);
+    }
+    Ok(steps)
+}
+
+fn set_int_field(data: &mut RSZData, name: &str, value: i64) -> Result<(), CommandError> {
+    match data.set_i64(name, value) {
+        true => Ok(()),
+        false => Err(CommandError(format!("{} has no integer field {}", data.name, name))),
+    }
+}
+
+//A command either lists its steps as child instances or is a single step itself
+fn step_instances(table: &DataListItem, command_id: u32) -> Option<Vec<i32>> {
+    let entry_instance = table.entry_instance(command_id)?;
+    let entry = table.data_rsz.instance_data(entry_instance)?;
+    match entry.find_field(INPUT_LIST_FIELD) {
+        Some(field) if field.value_type == TypeIDs::Object => Some(field.child_instances()),
+        _ => Some(vec![entry_instance]),
+    }
+}
+
+impl CharacterAsset {
+    fn charge_frames(&self, charge_id: u32) -> Option<i32> {
+        let charge = self.data_table(DataId::ChargeParamSub)?.entry(charge_id)?;
+        charge.get_i64(CHARGE_FRAME_FIELD).map(|frames| frames as i32)
+    }
+
+    pub fn command_steps(&self, command_id: u32) -> Option<Vec<CommandStep>> {
+        let table = self.data_table(DataId::CommandParamSub)?;
+        let mut steps: Vec<CommandStep> = vec![];
+        for instance in step_instances(table, command_id)? {
+            let step = table.data_rsz.instance_data(instance)?;
+            let charge_id = step.get_i64(CHARGE_ID_FIELD)
+                .filter(|charge_id| *charge_id >= 0)
+                .map(|charge_id| charge_id as u32);
+            steps.push(CommandStep {
+                key_flags: step.get_i64(KEY_FLAGS_FIELD).unwrap_or(0) as u32,
+                window: step.get_i64(WINDOW_FIELD).unwrap_or(0) as i32,
+                charge: charge_id.is_some(),
+                charge_id,
+                charge_frames: charge_id.and_then(|charge_id| self.charge_frames(charge_id)),
+            });
+        }
+        Some(steps)
+    }
+
+    pub fn decode_command(&self, command_id: u32) -> Option<DecodedCommand> {
+        let steps = self.command_steps(command_id)?;
+        let step_notations: Vec<String> = steps.iter().map(step_to_notation).collect();
+        Some(DecodedCommand {
+            id: command_id,
+            notation: step_notations.concat(),
+            step_notation: step_notations.join(" "),
+            steps,
+        })
+    }
+
+    pub fn decode_commands(&self) -> Vec<DecodedCommand> {
+        match self.data_table(DataId::CommandParamSub) {
+            Some(table) => table.data_ids.iter().filter_map(|id| self.decode_command(*id)).collect(),
+            None => vec![],
+        }
+    }
+
+    pub fn decode_command_groups(&self) -> Vec<DecodedCommandGroup> {
+        let table = match self.data_table(DataId::CommandGroup) {
+            Some(table) => table,
+            None => return vec![],
+        };
+        let mut groups: Vec<DecodedCommandGroup> = vec![];
+        for id in &table.data_ids {
+            let mut commands: Vec<u32> = vec![];
+            if let Some(group) = table.entry(*id) {
+                for field in &group.fields {
+                    if let RSZValue::List(values) = &field.value {
+                        commands.extend(values.iter().filter_map(|value| value.as_i64()).map(|command| command as u32));
+                    }
+                }
+            }
+            groups.push(DecodedCommandGroup {
+                id: *id,
+                notations: commands.iter()
+                    .filter_map(|command| self.decode_command(*command))
+                    .map(|command| command.notation)
+                    .collect(),
+                commands,
+            });
+        }
+        groups
+    }
+
+    //Writes the steps back into the existing step instances, the step count has to stay the same
+    pub fn set_command_steps(&mut self, command_id: u32, steps: &[CommandStep]) -> Result<(), CommandError> {
+        let missing = || CommandError(format!("command {} not found", command_id));
+        let table = self.data_table(DataId::CommandParamSub).ok_or_else(missing)?;
+        let instances = step_instances(table, command_id).ok_or_else(missing)?;
+        if instances.len() != steps.len() {
+            return Err(CommandError(format!("command {} has {} steps, got {}", command_id, instances.len(), steps.len())));
+        }
+        let mut charges: Vec<(u32, i32)> = vec![];
+        let table = self.data_table_mut(DataId::CommandParamSub).ok_or_else(missing)?;
+        for (instance, step) in instances.iter().zip(steps) {
+            let data = table.data_rsz.instance_data_mut(*instance).ok_or_else(missing)?;
+            set_int_field(data, KEY_FLAGS_FIELD, step.key_flags as i64)?;
+            set_int_field(data, WINDOW_FIELD, step.window as i64)?;
+            let old_charge_id = data.get_i64(CHARGE_ID_FIELD).filter(|charge_id| *charge_id >= 0);
+            match (old_charge_id, step.charge) {
+                (Some(charge_id), true) => {
+                    if let Some(charge_frames) = step.charge_frames {
+                        charges.push((charge_id as u32, charge_frames));
+                    }
+                }
+                (None, false) => {}
+                (_, true) => return Err(CommandError(format!("step {} of command {} has no charge entry", instance, command_id))),
+                (Some(_), false) => set_int_field(data, CHARGE_ID_FIELD, -1)?,
+            }
+        }
+        for (charge_id, charge_frames) in charges {
+            let charge = self.data_table_mut(DataId::ChargeParamSub)
+                .and_then(|table| table.entry_mut(charge_id))
+                .ok_or_else(|| CommandError(format!("charge {} not found", charge_id)))?;
+            set_int_field(charge, CHARGE_FRAME_FIELD, charge_frames as i64)?;
+        }
+        Ok(())
+    }
+
+    //Replaces the inputs of a command from notation while keeping its windows and charge times
+    pub fn encode_command(&mut self, command_id: u32, notation: &str) -> Result<(), CommandError> {
+        let old_steps = self.command_steps(command_id)
+            .ok_or_else(|| CommandError(format!("command {} not found", command_id)))?;
+        let mut steps = parse_notation(notation)?;
+        if old_steps.len() != steps.len() {
+            return Err(CommandError(format!("command {} has {} steps, {} has {}", command_id, old_steps.len(), notation, steps.len())));
+        }
+        for (step, old_step) in steps.iter_mut().zip(old_steps) {
+            step.window = old_step.window;
+            //a charge step takes over the step's charge entry, a step without one can't be made into a charge
+            if step.charge {
+                step.charge_id = old_step.charge_id;
+                step.charge_frames = old_step.charge_frames;
+            }
+        }
+        self.set_command_steps(command_id, &steps)
+    }
+}
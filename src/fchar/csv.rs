@@ -0,0 +1,19 @@
+//Quotes a value when it contains a separator, quote or line break
+pub fn escape_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    }
+    else {
+        value.to_string()
+    }
+}
+
+pub fn write_csv(header: &[&str], rows: &Vec<Vec<String>>) -> String {
+    let mut csv = header.iter().map(|column| escape_csv(column)).collect::<Vec<String>>().join(",");
+    csv.push('\n');
+    for row in rows {
+        csv.push_str(&row.iter().map(|value| escape_csv(value)).collect::<Vec<String>>().join(","));
+        csv.push('\n');
+    }
+    csv
+}
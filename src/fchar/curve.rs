@@ -0,0 +1,162 @@
+use serde::Serialize;
+
+use crate::fchar::{CharacterAsset, DataId, DataListItem};
+use crate::fchar::csv::write_csv;
+use crate::fchar::fields::{CURVE_KEYS_FIELD, CURVE_LENGTH_FIELD, KEY_FRAME_FIELD, KEY_POSITION_FIELD};
+use crate::rsz::json_parser::TypeIDs;
+use crate::rsz::RSZValue;
+
+pub const CURVE_TABLES: [DataId; 2] = [DataId::AttackOwnerCurve, DataId::AttackTargetCurve];
+
+#[derive(Serialize, Clone)]
+pub struct CurveKey {
+    pub frame: i32,
+    pub position: Vec<f64>,
+}
+
+#[derive(Serialize)]
+pub struct SampledCurve {
+    pub table: DataId,
+    pub id: u32,
+    pub keys: Vec<CurveKey>,
+    //one position per frame, keys are interpolated linearly
+    pub positions: Vec<Vec<f64>>,
+}
+
+//Keys are either child instances with a frame and position, or one position per frame
+fn curve_keys(table: &DataListItem, id: u32) -> Option<Vec<CurveKey>> {
+    let field = table.entry(id)?.find_field(CURVE_KEYS_FIELD)?;
+    let mut keys: Vec<CurveKey> = vec![];
+    if field.value_type == TypeIDs::Object {
+        for (n, instance_id) in field.child_instances().into_iter().enumerate() {
+            if let Some(key) = table.data_rsz.instance_data(instance_id) {
+                keys.push(CurveKey {
+                    frame: key.get_i64(KEY_FRAME_FIELD).unwrap_or(n as i64) as i32,
+                    position: key.find_field(KEY_POSITION_FIELD)
+                        .and_then(|field| field.value.components())
+                        .unwrap_or_default(),
+                });
+            }
+        }
+    } else {
+        let values = match &field.value {
+            RSZValue::List(values) => values,
+            _ => return None,
+        };
+        for (n, value) in values.iter().enumerate() {
+            keys.push(CurveKey {
+                frame: n as i32,
+                position: value.components().unwrap_or_default(),
+            });
+        }
+    }
+    keys.sort_by_key(|key| key.frame);
+    Some(keys)
+}
+
+pub fn sample_keys(keys: &[CurveKey], length: i32) -> Vec<Vec<f64>> {
+    let mut positions: Vec<Vec<f64>> = vec![];
+    for frame in 0..=length.max(0) {
+        let next = keys.iter().position(|key| key.frame >= frame);
+        let position = match next {
+            None => keys.last().map(|key| key.position.clone()).unwrap_or_default(),
+            Some(0) => keys[0].position.clone(),
+            Some(n) => {
+                let (previous, next) = (&keys[n - 1], &keys[n]);
+                let t = (frame - previous.frame) as f64 / (next.frame - previous.frame) as f64;
+                previous.position.iter().zip(&next.position)
+                    .map(|(from, to)| from + (to - from) * t)
+                    .collect()
+            }
+        };
+        positions.push(position);
+    }
+    positions
+}
+
+impl CharacterAsset {
+    pub fn sampled_curve(&self, table: DataId, id: u32) -> Option<SampledCurve> {
+        let data_list = self.data_table(table)?;
+        let keys = curve_keys(data_list, id)?;
+        let length = data_list.entry(id)?.get_i64(CURVE_LENGTH_FIELD)
+            .map(|length| length as i32)
+            .unwrap_or_else(|| keys.last().map(|key| key.frame).unwrap_or(0));
+        Some(SampledCurve {
+            table,
+            id,
+            positions: sample_keys(&keys, length),
+            keys,
+        })
+    }
+
+    pub fn sampled_curves(&self) -> Vec<SampledCurve> {
+        let mut curves: Vec<SampledCurve> = vec![];
+        for table in CURVE_TABLES {
+            if let Some(data_list) = self.data_table(table) {
+                curves.extend(data_list.data_ids.iter().filter_map(|id| self.sampled_curve(table, *id)));
+            }
+        }
+        curves
+    }
+}
+
+pub fn curves_to_csv(curves: &[SampledCurve]) -> String {
+    let mut rows: Vec<Vec<String>> = vec![];
+    for curve in curves {
+        for (frame, position) in curve.positions.iter().enumerate() {
+            rows.push(vec![
+                format!("{:?}", curve.table),
+                curve.id.to_string(),
+                frame.to_string(),
+                position.first().map(|x| x.to_string()).unwrap_or_default(),
+                position.get(1).map(|y| y.to_string()).unwrap_or_default(),
+                position.get(2).map(|z| z.to_string()).unwrap_or_default(),
+            ]);
+        }
+    }
+    write_csv(&["table", "id", "frame", "x", "y", "z"], &rows)
+}
+
+//Plots the x/y trajectory of every curve, owner curves in blue and target curves in red
+pub fn curves_to_svg(curves: &[SampledCurve]) -> String {
+    let points: Vec<(f64, f64)> = curves.iter()
+        .flat_map(|curve| curve.positions.iter())
+        .filter(|position| position.len() >= 2)
+        .map(|position| (position[0], position[1]))
+        .collect();
+    let min_x = points.iter().map(|point| point.0).fold(0.0, f64::min);
+    let max_x = points.iter().map(|point| point.0).fold(0.0, f64::max);
+    let min_y = points.iter().map(|point| point.1).fold(0.0, f64::min);
+    let max_y = points.iter().map(|point| point.1).fold(0.0, f64::max);
+    let width = (max_x - min_x).max(1.0);
+    let height = (max_y - min_y).max(1.0);
+    let margin = width.max(height) * 0.05;
+
+    //svg y points down, so the y values are flipped
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n",
+        min_x - margin, -max_y - margin, width + margin * 2.0, height + margin * 2.0,
+    );
+    let stroke = (width.max(height) / 400.0).max(0.01);
+    svg.push_str(&format!(
+        "  <line x1=\"{}\" y1=\"0\" x2=\"{}\" y2=\"0\" stroke=\"gray\" stroke-width=\"{}\"/>\n",
+        min_x - margin, max_x + margin, stroke,
+    ));
+    for curve in curves {
+        let color = match curve.table {
+            DataId::AttackOwnerCurve => "blue",
+            _ => "red",
+        };
+        let polyline = curve.positions.iter()
+            .filter(|position| position.len() >= 2)
+            .map(|position| format!("{},{}", position[0], -position[1]))
+            .collect::<Vec<String>>()
+            .join(" ");
+        svg.push_str(&format!(
+            "  <polyline fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" points=\"{}\"><title>{:?} {}</title></polyline>\n",
+            color, stroke, polyline, curve.table, curve.id,
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
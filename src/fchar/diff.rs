@@ -0,0 +1,314 @@
+use serde::Serialize;
+
+use crate::fchar::{ActionList, CharacterAsset, DataId};
+use crate::fchar::style::diff_rsz_data;
+use crate::fchar::timeline::ActionKey;
+use crate::rsz::{RSZData, RSZValue};
+
+#[derive(Serialize)]
+pub struct FieldChange {
+    pub class: String,
+    //which instance of the class, when several are compared at once
+    pub occurrence: usize,
+    pub field: String,
+    pub old: Option<RSZValue>,
+    pub new: Option<RSZValue>,
+}
+
+#[derive(Serialize)]
+pub struct FrameChange {
+    pub field: &'static str,
+    pub old: i32,
+    pub new: i32,
+}
+
+#[derive(Serialize)]
+pub struct KeyChange {
+    pub object_index: usize,
+    pub class: String,
+    //keys are matched by class and occurrence inside their object, so inserted keys don't shift the rest
+    pub occurrence: usize,
+    pub old_range: Option<(i32, i32)>,
+    pub new_range: Option<(i32, i32)>,
+    pub fields: Vec<FieldChange>,
+}
+
+#[derive(Serialize)]
+pub struct ActionChange {
+    pub action_id: i32,
+    pub frames: Vec<FrameChange>,
+    pub fields: Vec<FieldChange>,
+    pub keys: Vec<KeyChange>,
+}
+
+#[derive(Serialize)]
+pub struct RowChange {
+    pub table: DataId,
+    pub id: u32,
+    pub added: bool,
+    pub removed: bool,
+    pub fields: Vec<FieldChange>,
+}
+
+#[derive(Serialize)]
+pub struct FcharDiff {
+    pub added_actions: Vec<i32>,
+    pub removed_actions: Vec<i32>,
+    pub changed_actions: Vec<ActionChange>,
+    pub changed_rows: Vec<RowChange>,
+    pub personal_data: Vec<FieldChange>,
+}
+
+//Changed and added fields come from the newer data, removed ones only exist in the older data
+pub fn diff_fields(old: &[RSZData], new: &[RSZData]) -> Vec<FieldChange> {
+    let mut changes: Vec<FieldChange> = diff_rsz_data(old, new).into_iter()
+        .map(|change| FieldChange {
+            class: change.class,
+            occurrence: change.occurrence,
+            field: change.field,
+            old: change.inherited,
+            new: Some(change.value),
+        })
+        .collect();
+    changes.extend(diff_rsz_data(new, old).into_iter()
+        .filter(|change| change.inherited.is_none())
+        .map(|change| FieldChange {
+            class: change.class,
+            occurrence: change.occurrence,
+            field: change.field,
+            old: Some(change.value),
+            new: None,
+        }));
+    changes
+}
+
+fn diff_frames(old: &ActionList, new: &ActionList) -> Vec<FrameChange> {
+    let (old, new) = (&old.info.action_data, &new.info.action_data);
+    [
+        ("frames", old.frames, new.frames),
+        ("key_start_frame", old.key_start_frame, new.key_start_frame),
+        ("key_end_frame", old.key_end_frame, new.key_end_frame),
+    ].into_iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(field, old, new)| FrameChange { field, old, new })
+        .collect()
+}
+
+fn find_key<'a>(keys: &'a [ActionKey<'a>], object_index: usize, class: &str, occurrence: usize) -> Option<&'a ActionKey<'a>> {
+    keys.iter()
+        .filter(|key| key.object_index == object_index && key.data.name == class)
+        .nth(occurrence)
+}
+
+fn key_occurrence(keys: &[ActionKey], n: usize) -> usize {
+    keys[..n].iter()
+        .filter(|key| key.object_index == keys[n].object_index && key.data.name == keys[n].data.name)
+        .count()
+}
+
+fn diff_keys(old: &ActionList, new: &ActionList) -> Vec<KeyChange> {
+    let (old_keys, new_keys) = (old.keys(), new.keys());
+    let mut changes: Vec<KeyChange> = vec![];
+    for n in 0..new_keys.len() {
+        let key = &new_keys[n];
+        let occurrence = key_occurrence(&new_keys, n);
+        let old_key = find_key(&old_keys, key.object_index, &key.data.name, occurrence);
+        let change = KeyChange {
+            object_index: key.object_index,
+            class: key.data.name.clone(),
+            occurrence,
+            old_range: old_key.map(|old_key| (old_key.key_data.key_start_frame, old_key.key_data.key_end_frame)),
+            new_range: Some((key.key_data.key_start_frame, key.key_data.key_end_frame)),
+            fields: match old_key {
+                Some(old_key) => diff_fields(std::slice::from_ref(old_key.data), std::slice::from_ref(key.data)),
+                None => vec![],
+            },
+        };
+        if change.old_range != change.new_range || !change.fields.is_empty() {
+            changes.push(change);
+        }
+    }
+    for n in 0..old_keys.len() {
+        let key = &old_keys[n];
+        let occurrence = key_occurrence(&old_keys, n);
+        if find_key(&new_keys, key.object_index, &key.data.name, occurrence).is_none() {
+            changes.push(KeyChange {
+                object_index: key.object_index,
+                class: key.data.name.clone(),
+                occurrence,
+                old_range: Some((key.key_data.key_start_frame, key.key_data.key_end_frame)),
+                new_range: None,
+                fields: vec![],
+            });
+        }
+    }
+    changes.sort_by_key(|change| change.object_index);
+    changes
+}
+
+fn diff_rows(old: &CharacterAsset, new: &CharacterAsset, table: DataId) -> Vec<RowChange> {
+    let old_table = old.data_table(table);
+    let new_table = new.data_table(table);
+    let mut ids: Vec<u32> = old_table.iter().chain(new_table.iter())
+        .flat_map(|data_list| data_list.data_ids.iter().copied())
+        .collect();
+    ids.sort();
+    ids.dedup();
+    let mut changes: Vec<RowChange> = vec![];
+    for id in ids {
+        let old_entry = old_table.and_then(|data_list| data_list.entry(id));
+        let new_entry = new_table.and_then(|data_list| data_list.entry(id));
+        let fields = diff_fields(
+            old_entry.map(std::slice::from_ref).unwrap_or_default(),
+            new_entry.map(std::slice::from_ref).unwrap_or_default(),
+        );
+        if !fields.is_empty() {
+            changes.push(RowChange {
+                table,
+                id,
+                added: old_entry.is_none(),
+                removed: new_entry.is_none(),
+                fields,
+            });
+        }
+    }
+    changes
+}
+
+//Actions are paired by id and by how many actions with the same id come before them, so repeated ids still line up
+fn action_occurrence(actions: &[ActionList], n: usize) -> usize {
+    let action_id = actions[n].info.action_data.action_id;
+    actions[..n].iter().filter(|action| action.info.action_data.action_id == action_id).count()
+}
+
+fn find_action(actions: &[ActionList], action_id: i32, occurrence: usize) -> Option<&ActionList> {
+    actions.iter()
+        .filter(|action| action.info.action_data.action_id == action_id)
+        .nth(occurrence)
+}
+
+impl CharacterAsset {
+    //Compares this file against a newer version of the same character
+    pub fn patch_diff(&self, new: &CharacterAsset) -> FcharDiff {
+        let mut diff = FcharDiff {
+            added_actions: vec![],
+            removed_actions: vec![],
+            changed_actions: vec![],
+            changed_rows: vec![],
+            personal_data: diff_fields(&self.personal_data.data, &new.personal_data.data),
+        };
+        for (n, new_action) in new.action_list.iter().enumerate() {
+            let action_id = new_action.info.action_data.action_id;
+            let old_action = match find_action(&self.action_list, action_id, action_occurrence(&new.action_list, n)) {
+                Some(old_action) => old_action,
+                None => {
+                    diff.added_actions.push(action_id);
+                    continue;
+                }
+            };
+            let change = ActionChange {
+                action_id,
+                frames: diff_frames(old_action, new_action),
+                fields: diff_fields(&old_action.action.data, &new_action.action.data),
+                keys: diff_keys(old_action, new_action),
+            };
+            if !change.frames.is_empty() || !change.fields.is_empty() || !change.keys.is_empty() {
+                diff.changed_actions.push(change);
+            }
+        }
+        diff.removed_actions = (0..self.action_list.len())
+            .filter(|n| find_action(&new.action_list, self.action_list[*n].info.action_data.action_id, action_occurrence(&self.action_list, *n)).is_none())
+            .map(|n| self.action_list[n].info.action_data.action_id)
+            .collect();
+
+        let mut tables = self.data_id_table.clone();
+        tables.extend(new.data_id_table.iter().filter(|table| !self.data_id_table.contains(table)));
+        for table in tables {
+            diff.changed_rows.extend(diff_rows(self, new, table));
+        }
+        diff
+    }
+}
+
+fn value_to_string(value: &Option<RSZValue>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "-".to_string(),
+    }
+}
+
+fn range_to_string(range: &Option<(i32, i32)>) -> String {
+    match range {
+        Some((start, end)) => format!("{}-{}", start, end),
+        None => "-".to_string(),
+    }
+}
+
+fn push_field_lines(markdown: &mut String, fields: &[FieldChange]) {
+    for field in fields {
+        markdown.push_str(&format!(
+            "- {}.{}: {} → {}\n",
+            field.class, field.field, value_to_string(&field.old), value_to_string(&field.new),
+        ));
+    }
+}
+
+impl FcharDiff {
+    //Written to be pasted into patch notes, one section per kind of change
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Patch diff\n");
+        if !self.added_actions.is_empty() {
+            markdown.push_str("\n## Added actions\n\n");
+            for action_id in &self.added_actions {
+                markdown.push_str(&format!("- {}\n", action_id));
+            }
+        }
+        if !self.removed_actions.is_empty() {
+            markdown.push_str("\n## Removed actions\n\n");
+            for action_id in &self.removed_actions {
+                markdown.push_str(&format!("- {}\n", action_id));
+            }
+        }
+        if !self.changed_actions.is_empty() {
+            markdown.push_str("\n## Changed actions\n");
+            for action in &self.changed_actions {
+                markdown.push_str(&format!("\n### Action {}\n\n", action.action_id));
+                for frame in &action.frames {
+                    markdown.push_str(&format!("- {}: {} → {}\n", frame.field, frame.old, frame.new));
+                }
+                push_field_lines(&mut markdown, &action.fields);
+                for key in &action.keys {
+                    if key.old_range != key.new_range {
+                        markdown.push_str(&format!(
+                            "- object {} {} #{}: frames {} → {}\n",
+                            key.object_index, key.class, key.occurrence, range_to_string(&key.old_range), range_to_string(&key.new_range),
+                        ));
+                    }
+                    for field in &key.fields {
+                        markdown.push_str(&format!(
+                            "- object {} {} #{} {}: {} → {}\n",
+                            key.object_index, key.class, key.occurrence, field.field, value_to_string(&field.old), value_to_string(&field.new),
+                        ));
+                    }
+                }
+            }
+        }
+        if !self.changed_rows.is_empty() {
+            markdown.push_str("\n## Changed data\n");
+            for row in &self.changed_rows {
+                let status = match (row.added, row.removed) {
+                    (true, _) => " (added)",
+                    (_, true) => " (removed)",
+                    _ => "",
+                };
+                markdown.push_str(&format!("\n### {:?} {}{}\n\n", row.table, row.id, status));
+                push_field_lines(&mut markdown, &row.fields);
+            }
+        }
+        if !self.personal_data.is_empty() {
+            markdown.push_str("\n## Personal data\n\n");
+            push_field_lines(&mut markdown, &self.personal_data);
+        }
+        markdown
+    }
+}
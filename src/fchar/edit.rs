@@ -0,0 +1,266 @@
+use std::fmt;
+
+use crate::fchar::{ActionList, CharacterAsset, KeyData, Object};
+use crate::fchar::fields::TRIGGER_ACTION_FIELD;
+use crate::rsz::RSZ;
+
+#[derive(Debug, Clone)]
+pub struct EditError(pub String);
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Edit error: {}", self.0)
+    }
+}
+
+impl std::error::Error for EditError {}
+
+impl RSZ {
+    pub fn sync_counts(&mut self) {
+        self.header.object_count = self.object_table.len() as i32;
+        self.header.instance_count = self.instance_infos.len() as i32;
+        self.header.userdata_count = self.userdata_infos.len() as i32;
+    }
+
+    //Copies an instance and every instance below it, children are appended before their parent like the game stores them
+    fn clone_instance(&mut self, instance_id: i32) -> Option<i32> {
+        let mut data = self.instance_data(instance_id)?.clone();
+        for field in &mut data.fields {
+            //userdata and missing instances have no data to copy, so they stay shared. Children always come
+            //before their parent, so a reference to a later instance is left alone rather than followed into a cycle
+            field.map_child_instances(|child_id| match child_id < instance_id {
+                true => self.clone_instance(child_id).unwrap_or(child_id),
+                false => child_id,
+            });
+        }
+        let instance_info = self.instance_infos.get(instance_id as usize)?.clone();
+        let new_instance_id = self.instance_infos.len() as i32;
+        self.instance_infos.push(instance_info);
+        self.data.push(data);
+        Some(new_instance_id)
+    }
+
+    //Appends a copy of a root instance as a new root instance, the instances it references are copied with it
+    pub fn clone_root_instance(&mut self, root_index: usize) -> Option<i32> {
+        let instance_id = *self.object_table.get(root_index)?;
+        let new_instance_id = self.clone_instance(instance_id)?;
+        self.object_table.push(new_instance_id);
+        self.sync_counts();
+        Some(new_instance_id)
+    }
+
+    //Collects the instance and every instance with data below it
+    fn instance_tree(&self, instance_id: i32, ids: &mut Vec<i32>) {
+        let data = match self.instance_data(instance_id) {
+            Some(data) => data,
+            None => return,
+        };
+        if ids.contains(&instance_id) {
+            return;
+        }
+        ids.push(instance_id);
+        for child_id in data.fields.iter().flat_map(|field| field.child_instances()) {
+            self.instance_tree(child_id, ids);
+        }
+    }
+
+    //Drops a root instance along with the instances below it that no other root instance uses, the rest are renumbered
+    pub fn remove_root_instance(&mut self, root_index: usize) -> Option<i32> {
+        if root_index >= self.object_table.len() {
+            return None;
+        }
+        let instance_id = self.object_table.remove(root_index);
+        let mut kept: Vec<i32> = vec![];
+        for root_id in self.object_table.clone() {
+            self.instance_tree(root_id, &mut kept);
+        }
+        let mut removed: Vec<i32> = vec![];
+        self.instance_tree(instance_id, &mut removed);
+        removed.retain(|id| !kept.contains(id));
+
+        let mut data_indices: Vec<usize> = removed.iter().filter_map(|id| self.data_index(*id)).collect();
+        data_indices.sort();
+        for index in data_indices.into_iter().rev() {
+            self.data.remove(index);
+        }
+        let new_ids: Vec<i32> = (0..self.instance_infos.len() as i32)
+            .map(|id| id - removed.iter().filter(|removed_id| **removed_id < id).count() as i32)
+            .collect();
+        removed.sort();
+        for id in removed.iter().rev() {
+            self.instance_infos.remove(*id as usize);
+        }
+        let new_id = |id: i32| new_ids.get(id as usize).copied().unwrap_or(id);
+        for root_id in &mut self.object_table {
+            *root_id = new_id(*root_id);
+        }
+        for userdata_info in &mut self.userdata_infos {
+            userdata_info.instance_id = new_id(userdata_info.instance_id as i32) as u32;
+        }
+        for data in &mut self.data {
+            for field in &mut data.fields {
+                field.map_child_instances(new_id);
+            }
+        }
+        self.sync_counts();
+        Some(instance_id)
+    }
+}
+
+impl ActionList {
+    //action_count mirrors the root instances of the action's own RSZ, the action's key range spans every key
+    pub fn sync_counts(&mut self) {
+        self.info.object_count = self.objects.len() as u32;
+        for object in &mut self.objects {
+            object.info.object_data.data_count = object.info.object_data.key_data.len() as i32;
+            object.action.sync_counts();
+        }
+        self.action.sync_counts();
+        self.info.action_count = self.action.object_table.len() as u32;
+        let key_data = || self.objects.iter().flat_map(|object| object.info.object_data.key_data.iter());
+        if let (Some(start), Some(end)) = (key_data().map(|key| key.key_start_frame).min(), key_data().map(|key| key.key_end_frame).max()) {
+            self.info.action_data.key_start_frame = start;
+            self.info.action_data.key_end_frame = end;
+        }
+    }
+
+    fn object_mut(&mut self, object_index: usize) -> Result<&mut Object, EditError> {
+        let action_id = self.info.action_data.action_id;
+        self.objects.get_mut(object_index)
+            .ok_or_else(|| EditError(format!("action {} has no object {}", action_id, object_index)))
+    }
+
+    pub fn add_object(&mut self, object: Object) -> usize {
+        self.objects.push(object);
+        self.sync_counts();
+        self.objects.len() - 1
+    }
+
+    pub fn clone_object(&mut self, object_index: usize) -> Result<usize, EditError> {
+        let object = self.object_mut(object_index)?.clone();
+        Ok(self.add_object(object))
+    }
+
+    pub fn remove_object(&mut self, object_index: usize) -> Result<Object, EditError> {
+        self.object_mut(object_index)?;
+        let object = self.objects.remove(object_index);
+        self.sync_counts();
+        Ok(object)
+    }
+
+    //Adds a key range described by a copy of an existing key of the same object
+    pub fn add_key(&mut self, object_index: usize, template_key_index: usize, key_data: KeyData) -> Result<usize, EditError> {
+        let object = self.object_mut(object_index)?;
+        object.action.clone_root_instance(template_key_index)
+            .ok_or_else(|| EditError(format!("object {} has no key {}", object_index, template_key_index)))?;
+        object.info.object_data.key_data.push(key_data);
+        let key_index = object.info.object_data.key_data.len() - 1;
+        self.sync_counts();
+        Ok(key_index)
+    }
+
+    //The key's instances are removed with it, unless another key still uses them
+    pub fn remove_key(&mut self, object_index: usize, key_index: usize) -> Result<KeyData, EditError> {
+        let object = self.object_mut(object_index)?;
+        if key_index >= object.info.object_data.key_data.len() {
+            return Err(EditError(format!("object {} has no key {}", object_index, key_index)));
+        }
+        let key_data = object.info.object_data.key_data.remove(key_index);
+        object.action.remove_root_instance(key_index);
+        self.sync_counts();
+        Ok(key_data)
+    }
+}
+
+impl CharacterAsset {
+    pub fn sync_action_counts(&mut self) {
+        for action in &mut self.action_list {
+            action.sync_counts();
+        }
+        self.action_list_table.action_list_count = self.action_list.len() as u32;
+        self.action_list_table.object_count = self.action_list.iter()
+            .map(|action| action.objects.len() as u32)
+            .sum();
+    }
+
+    pub fn action_mut(&mut self, action_id: i32) -> Result<&mut ActionList, EditError> {
+        self.action_list.iter_mut()
+            .find(|action| action.info.action_data.action_id == action_id)
+            .ok_or_else(|| EditError(format!("action {} not found", action_id)))
+    }
+
+    pub fn add_action(&mut self, action: ActionList) -> Result<(), EditError> {
+        let action_id = action.info.action_data.action_id;
+        if self.action(action_id).is_some() {
+            return Err(EditError(format!("action {} already exists", action_id)));
+        }
+        self.action_list.push(action);
+        self.sync_action_counts();
+        Ok(())
+    }
+
+    //Copies an action under a new id, to be used as a template for a new move. The copy's own RSZ and the
+    //string table entries of the original also get the new id
+    pub fn clone_action(&mut self, action_id: i32, new_action_id: i32) -> Result<&mut ActionList, EditError> {
+        let mut action = self.action(action_id)
+            .ok_or_else(|| EditError(format!("action {} not found", action_id)))?
+            .clone();
+        action.info.action_data.action_id = new_action_id;
+        for data in &mut action.action.data {
+            if let Some(field) = data.find_field_mut(TRIGGER_ACTION_FIELD).filter(|field| field.value.as_i64() == Some(action_id as i64)) {
+                field.value.set_i64(new_action_id as i64);
+            }
+        }
+        self.add_action(action)?;
+        for string_info in &mut self.string_table {
+            if string_info.action_ids.contains(&action_id) && !string_info.action_ids.contains(&new_action_id) {
+                string_info.action_ids.push(new_action_id);
+            }
+        }
+        self.action_mut(new_action_id)
+    }
+
+    pub fn remove_action(&mut self, action_id: i32) -> Result<ActionList, EditError> {
+        let index = self.action_list.iter()
+            .position(|action| action.info.action_data.action_id == action_id)
+            .ok_or_else(|| EditError(format!("action {} not found", action_id)))?;
+        let action = self.action_list.remove(index);
+        if self.action(action_id).is_none() {
+            for string_info in &mut self.string_table {
+                string_info.action_ids.retain(|id| *id != action_id);
+            }
+        }
+        self.sync_action_counts();
+        Ok(action)
+    }
+
+    pub fn add_object(&mut self, action_id: i32, object: Object) -> Result<usize, EditError> {
+        let object_index = self.action_mut(action_id)?.add_object(object);
+        self.sync_action_counts();
+        Ok(object_index)
+    }
+
+    pub fn clone_object(&mut self, action_id: i32, object_index: usize) -> Result<usize, EditError> {
+        let object_index = self.action_mut(action_id)?.clone_object(object_index)?;
+        self.sync_action_counts();
+        Ok(object_index)
+    }
+
+    pub fn remove_object(&mut self, action_id: i32, object_index: usize) -> Result<Object, EditError> {
+        let object = self.action_mut(action_id)?.remove_object(object_index)?;
+        self.sync_action_counts();
+        Ok(object)
+    }
+
+    pub fn add_key(&mut self, action_id: i32, object_index: usize, template_key_index: usize, key_data: KeyData) -> Result<usize, EditError> {
+        let key_index = self.action_mut(action_id)?.add_key(object_index, template_key_index, key_data)?;
+        self.sync_action_counts();
+        Ok(key_index)
+    }
+
+    pub fn remove_key(&mut self, action_id: i32, object_index: usize, key_index: usize) -> Result<KeyData, EditError> {
+        let key_data = self.action_mut(action_id)?.remove_key(object_index, key_index)?;
+        self.sync_action_counts();
+        Ok(key_data)
+    }
+}
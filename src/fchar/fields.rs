@@ -0,0 +1,140 @@
+use crate::rsz::json_parser::get_field_names;
+use crate::rsz::normalize_field_name;
+
+//Class and field names the fchar modules look up, in the PascalCase of the SF6 schema dump. The repo doesn't ship a
+//dump, so missing_schema_fields checks them against whichever one is loaded. Lookups go through RSZData::find_field,
+//which ignores case and underscores, so a dump spelling OkKeyFlags as ok_key_flags still matches
+
+//Attack data table entries and the keys pointing at them
+pub(crate) const DAMAGE_FIELD: &str = "DamageValue";
+pub(crate) const STUN_FIELD: &str = "PiyoValue";
+pub(crate) const DRIVE_GAIN_FIELD: &str = "FocusOwn";
+pub(crate) const DRIVE_DAMAGE_FIELD: &str = "FocusTgt";
+pub(crate) const SUPER_GAIN_FIELD: &str = "SuperOwn";
+pub(crate) const HIT_STOP_FIELD: &str = "HitStop";
+pub(crate) const HIT_STUN_FIELD: &str = "HitStun";
+pub(crate) const BLOCK_STUN_FIELD: &str = "GuardStun";
+pub(crate) const ATTACK_KEY_FIELD: &str = "AttackDataListIndex";
+pub(crate) const COMMON_ATTACK_KEY_FIELD: &str = "CommonAttackDataIndex";
+pub(crate) const KARMA_ATTACK_KEY_FIELD: &str = "KarmaAttackDataIndex";
+
+//Personal data
+pub(crate) const VITAL_FIELD: &str = "Vital";
+pub(crate) const WALK_FORWARD_SPEED_FIELD: &str = "FrontWalkSpeed";
+pub(crate) const WALK_BACK_SPEED_FIELD: &str = "BackWalkSpeed";
+pub(crate) const JUMP_SPEED_FIELD: &str = "JumpSpeed";
+pub(crate) const JUMP_FORWARD_SPEED_FIELD: &str = "FrontJumpSpeed";
+pub(crate) const JUMP_BACK_SPEED_FIELD: &str = "BackJumpSpeed";
+pub(crate) const GRAVITY_FIELD: &str = "Gravity";
+pub(crate) const JUMP_FRAME_FIELD: &str = "JumpFrame";
+
+//Camera keys and the camera, camera box and screen vibration tables
+pub(crate) const CAMERA_KEY_FIELD: &str = "CameraDataIndex";
+pub(crate) const CAMERA_BOX_KEY_FIELD: &str = "CameraBoxIndex";
+pub(crate) const VIBRATION_KEY_FIELD: &str = "ScreenVibrationIndex";
+pub(crate) const POSITION_FIELD: &str = "Position";
+pub(crate) const TARGET_FIELD: &str = "Target";
+pub(crate) const FOV_FIELD: &str = "FOV";
+pub(crate) const RECT_FIELD: &str = "Rect";
+pub(crate) const VIBRATION_POWER_FIELD: &str = "Power";
+pub(crate) const VIBRATION_FRAME_FIELD: &str = "Frame";
+pub(crate) const VIBRATION_ATTENUATION_FIELD: &str = "Attenuation";
+
+//Trigger keys and the trigger tables
+pub(crate) const TRIGGER_KEY_CLASS: &str = "TriggerKey";
+pub(crate) const TRIGGER_GROUP_FIELD: &str = "TriggerGroup";
+pub(crate) const CONDITION_FIELD: &str = "ConditionFlag";
+pub(crate) const TRIGGER_ACTION_FIELD: &str = "ActionID";
+pub(crate) const TRIGGER_COMMAND_FIELD: &str = "CommandNo";
+
+//Command steps and the charge table
+pub(crate) const INPUT_LIST_FIELD: &str = "Inputs";
+pub(crate) const KEY_FLAGS_FIELD: &str = "OkKeyFlags";
+pub(crate) const WINDOW_FIELD: &str = "FrameNum";
+pub(crate) const CHARGE_ID_FIELD: &str = "ChargeId";
+pub(crate) const CHARGE_FRAME_FIELD: &str = "ChargeFrame";
+
+//Owner and target curves
+pub(crate) const CURVE_KEYS_FIELD: &str = "Keys";
+pub(crate) const CURVE_LENGTH_FIELD: &str = "Length";
+pub(crate) const KEY_FRAME_FIELD: &str = "Frame";
+pub(crate) const KEY_POSITION_FIELD: &str = "Position";
+
+//Shot keys and the projectile tables
+pub(crate) const SHOT_KEY_FIELD: &str = "ShotDataIndex";
+pub(crate) const SPEED_FIELD: &str = "Speed";
+pub(crate) const LIFETIME_FIELD: &str = "LifeTime";
+pub(crate) const HIT_COUNT_FIELD: &str = "HitNum";
+pub(crate) const BOX_LIST_FIELD: &str = "BoxList";
+pub(crate) const BOX_RECT_FIELD: &str = "Rect";
+
+//Voice and facial keys
+pub(crate) const VOICE_FACIAL_KEY_FIELD: &str = "VoiceFacialDataIndex";
+pub(crate) const VOICE_FIELD: &str = "VoiceID";
+pub(crate) const FACIAL_FIELD: &str = "FacialID";
+
+const FIELD_NAMES: &[&str] = &[
+    DAMAGE_FIELD,
+    STUN_FIELD,
+    DRIVE_GAIN_FIELD,
+    DRIVE_DAMAGE_FIELD,
+    SUPER_GAIN_FIELD,
+    HIT_STOP_FIELD,
+    HIT_STUN_FIELD,
+    BLOCK_STUN_FIELD,
+    ATTACK_KEY_FIELD,
+    COMMON_ATTACK_KEY_FIELD,
+    KARMA_ATTACK_KEY_FIELD,
+    VITAL_FIELD,
+    WALK_FORWARD_SPEED_FIELD,
+    WALK_BACK_SPEED_FIELD,
+    JUMP_SPEED_FIELD,
+    JUMP_FORWARD_SPEED_FIELD,
+    JUMP_BACK_SPEED_FIELD,
+    GRAVITY_FIELD,
+    JUMP_FRAME_FIELD,
+    CAMERA_KEY_FIELD,
+    CAMERA_BOX_KEY_FIELD,
+    VIBRATION_KEY_FIELD,
+    POSITION_FIELD,
+    TARGET_FIELD,
+    FOV_FIELD,
+    RECT_FIELD,
+    VIBRATION_POWER_FIELD,
+    VIBRATION_FRAME_FIELD,
+    VIBRATION_ATTENUATION_FIELD,
+    TRIGGER_GROUP_FIELD,
+    CONDITION_FIELD,
+    TRIGGER_ACTION_FIELD,
+    TRIGGER_COMMAND_FIELD,
+    INPUT_LIST_FIELD,
+    KEY_FLAGS_FIELD,
+    WINDOW_FIELD,
+    CHARGE_ID_FIELD,
+    CHARGE_FRAME_FIELD,
+    CURVE_KEYS_FIELD,
+    CURVE_LENGTH_FIELD,
+    KEY_FRAME_FIELD,
+    KEY_POSITION_FIELD,
+    SHOT_KEY_FIELD,
+    SPEED_FIELD,
+    LIFETIME_FIELD,
+    HIT_COUNT_FIELD,
+    BOX_LIST_FIELD,
+    BOX_RECT_FIELD,
+    VOICE_FACIAL_KEY_FIELD,
+    VOICE_FIELD,
+    FACIAL_FIELD,
+];
+
+//Field names the fchar modules look up that no class of the loaded schema has, each listed once
+pub fn missing_schema_fields() -> Vec<&'static str> {
+    let schema_names: Vec<String> = get_field_names().iter().map(|name| normalize_field_name(name)).collect();
+    let mut missing: Vec<&'static str> = vec![];
+    for name in FIELD_NAMES {
+        if !missing.contains(name) && !schema_names.contains(&normalize_field_name(name)) {
+            missing.push(name);
+        }
+    }
+    missing
+}
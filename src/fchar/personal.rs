@@ -0,0 +1,202 @@
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+
+use crate::detect::{detect, FileKind};
+use crate::fchar::{parse_fchar, CharacterAsset};
+use crate::profile::GameProfile;
+use crate::fchar::csv::write_csv;
+use crate::fchar::fields::{GRAVITY_FIELD, JUMP_BACK_SPEED_FIELD, JUMP_FORWARD_SPEED_FIELD, JUMP_FRAME_FIELD, JUMP_SPEED_FIELD, VITAL_FIELD, WALK_BACK_SPEED_FIELD, WALK_FORWARD_SPEED_FIELD};
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+pub enum PersonalField {
+    Health,
+    WalkForwardSpeed,
+    WalkBackSpeed,
+    JumpSpeed,
+    JumpForwardSpeed,
+    JumpBackSpeed,
+    Gravity,
+    JumpFrames,
+}
+
+pub const PERSONAL_FIELDS: [PersonalField; 8] = [
+    PersonalField::Health,
+    PersonalField::WalkForwardSpeed,
+    PersonalField::WalkBackSpeed,
+    PersonalField::JumpSpeed,
+    PersonalField::JumpForwardSpeed,
+    PersonalField::JumpBackSpeed,
+    PersonalField::Gravity,
+    PersonalField::JumpFrames,
+];
+
+impl PersonalField {
+    pub fn field_name(&self) -> &'static str {
+        match self {
+            PersonalField::Health => VITAL_FIELD,
+            PersonalField::WalkForwardSpeed => WALK_FORWARD_SPEED_FIELD,
+            PersonalField::WalkBackSpeed => WALK_BACK_SPEED_FIELD,
+            PersonalField::JumpSpeed => JUMP_SPEED_FIELD,
+            PersonalField::JumpForwardSpeed => JUMP_FORWARD_SPEED_FIELD,
+            PersonalField::JumpBackSpeed => JUMP_BACK_SPEED_FIELD,
+            PersonalField::Gravity => GRAVITY_FIELD,
+            PersonalField::JumpFrames => JUMP_FRAME_FIELD,
+        }
+    }
+
+    pub fn column_name(&self) -> &'static str {
+        match self {
+            PersonalField::Health => "health",
+            PersonalField::WalkForwardSpeed => "walk_forward_speed",
+            PersonalField::WalkBackSpeed => "walk_back_speed",
+            PersonalField::JumpSpeed => "jump_speed",
+            PersonalField::JumpForwardSpeed => "jump_forward_speed",
+            PersonalField::JumpBackSpeed => "jump_back_speed",
+            PersonalField::Gravity => "gravity",
+            PersonalField::JumpFrames => "jump_frames",
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct PersonalParameters {
+    pub health: Option<f64>,
+    pub walk_forward_speed: Option<f64>,
+    pub walk_back_speed: Option<f64>,
+    pub jump_speed: Option<f64>,
+    pub jump_forward_speed: Option<f64>,
+    pub jump_back_speed: Option<f64>,
+    pub gravity: Option<f64>,
+    pub jump_frames: Option<f64>,
+}
+
+impl PersonalParameters {
+    pub fn get(&self, field: PersonalField) -> Option<f64> {
+        match field {
+            PersonalField::Health => self.health,
+            PersonalField::WalkForwardSpeed => self.walk_forward_speed,
+            PersonalField::WalkBackSpeed => self.walk_back_speed,
+            PersonalField::JumpSpeed => self.jump_speed,
+            PersonalField::JumpForwardSpeed => self.jump_forward_speed,
+            PersonalField::JumpBackSpeed => self.jump_back_speed,
+            PersonalField::Gravity => self.gravity,
+            PersonalField::JumpFrames => self.jump_frames,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CharacterParameters {
+    pub file_name: String,
+    pub parameters: PersonalParameters,
+}
+
+impl CharacterAsset {
+    //The parameters are spread over several instances, the first one holding the field is used
+    pub fn personal_value(&self, field: PersonalField) -> Option<f64> {
+        self.personal_data.data.iter().find_map(|data| data.get_f64(field.field_name()))
+    }
+
+    //Returns false if no instance of the personal data has the field
+    pub fn set_personal_value(&mut self, field: PersonalField, value: f64) -> bool {
+        self.personal_data.data.iter_mut()
+            .find(|data| data.find_field(field.field_name()).is_some())
+            .map(|data| data.set_f64(field.field_name(), value))
+            .unwrap_or(false)
+    }
+
+    pub fn personal_parameters(&self) -> PersonalParameters {
+        PersonalParameters {
+            health: self.personal_value(PersonalField::Health),
+            walk_forward_speed: self.personal_value(PersonalField::WalkForwardSpeed),
+            walk_back_speed: self.personal_value(PersonalField::WalkBackSpeed),
+            jump_speed: self.personal_value(PersonalField::JumpSpeed),
+            jump_forward_speed: self.personal_value(PersonalField::JumpForwardSpeed),
+            jump_back_speed: self.personal_value(PersonalField::JumpBackSpeed),
+            gravity: self.personal_value(PersonalField::Gravity),
+            jump_frames: self.personal_value(PersonalField::JumpFrames),
+        }
+    }
+}
+
+fn sorted_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+//The game of the first fchar file by name decides the profile of the whole folder, None if it holds no fchar file
+pub fn folder_profile(dir: &Path) -> std::io::Result<Option<&'static GameProfile>> {
+    for path in sorted_files(dir)? {
+        let buffer = std::fs::read(&path)?;
+        match detect(&buffer, &path.to_string_lossy()) {
+            Some(file_type) if file_type.kind == FileKind::CharacterAsset => return Ok(Some(file_type.profile())),
+            _ => continue,
+        }
+    }
+    Ok(None)
+}
+
+#[derive(Serialize)]
+pub struct SkippedFile {
+    pub file_name: String,
+    pub reason: String,
+}
+
+#[derive(Serialize)]
+pub struct PersonalComparison {
+    pub characters: Vec<CharacterParameters>,
+    //fchar files that were left out, from another game or failing to parse
+    pub skipped: Vec<SkippedFile>,
+}
+
+//Parses every fchar file in the folder from the game the loaded schema belongs to, sorted by file name. Files are
+//told apart by their header, and one that fails to parse is listed in skipped rather than ending the run
+pub fn compare_personal_data(dir: &Path, profile: &GameProfile) -> std::io::Result<PersonalComparison> {
+    let paths = sorted_files(dir)?;
+    let mut comparison = PersonalComparison {
+        characters: vec![],
+        skipped: vec![],
+    };
+    for path in paths {
+        let buffer = std::fs::read(&path)?;
+        let file_type = match detect(&buffer, &path.to_string_lossy()) {
+            Some(file_type) if file_type.kind == FileKind::CharacterAsset => file_type,
+            _ => continue,
+        };
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        if file_type.profile().game != profile.game {
+            comparison.skipped.push(SkippedFile {
+                file_name,
+                reason: format!("it is from {:?}", file_type.profile().game),
+            });
+            continue;
+        }
+        match parse_fchar(&buffer, profile) {
+            Ok((_, fchar_file)) => comparison.characters.push(CharacterParameters {
+                file_name,
+                parameters: fchar_file.personal_parameters(),
+            }),
+            Err(error) => comparison.skipped.push(SkippedFile {
+                file_name,
+                reason: format!("it could not be parsed: {}", error),
+            }),
+        }
+    }
+    Ok(comparison)
+}
+
+pub fn personal_data_to_csv(characters: &[CharacterParameters]) -> String {
+    let mut header = vec!["file_name"];
+    header.extend(PERSONAL_FIELDS.iter().map(|field| field.column_name()));
+    let rows = characters.iter().map(|character| {
+        let mut row = vec![character.file_name.clone()];
+        row.extend(PERSONAL_FIELDS.iter().map(|field| character.parameters.get(*field).map(|value| value.to_string()).unwrap_or_default()));
+        row
+    }).collect();
+    write_csv(&header, &rows)
+}
@@ -0,0 +1,118 @@
+use serde::Serialize;
+
+use crate::fchar::{CharacterAsset, DataId};
+use crate::fchar::csv::write_csv;
+use crate::fchar::fields::{BOX_LIST_FIELD, BOX_RECT_FIELD, HIT_COUNT_FIELD, LIFETIME_FIELD, SHOT_KEY_FIELD, SPEED_FIELD};
+use crate::rsz::{RSZData, RSZValue};
+
+#[derive(Serialize)]
+pub struct ProjectileSpawn {
+    pub action_id: i32,
+    pub frame: i32,
+}
+
+#[derive(Serialize)]
+pub struct ProjectileBoxSize {
+    pub id: u32,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Serialize)]
+pub struct ProjectileSummary {
+    pub id: u32,
+    //distance travelled per frame on each axis
+    pub speed: Vec<f64>,
+    pub lifetime: Option<i64>,
+    pub hits: Option<i64>,
+    pub boxes: Vec<ProjectileBoxSize>,
+    pub spawned_by: Vec<ProjectileSpawn>,
+}
+
+impl CharacterAsset {
+    pub fn key_projectile(&self, key: &RSZData) -> Option<(u32, &RSZData)> {
+        let id = key.get_i64(SHOT_KEY_FIELD).filter(|id| *id >= 0)? as u32;
+        Some((id, self.data_table(DataId::ProjectileData)?.entry(id)?))
+    }
+
+    pub fn projectile_spawns(&self, projectile_id: u32) -> Vec<ProjectileSpawn> {
+        let mut spawns: Vec<ProjectileSpawn> = vec![];
+        for action in &self.action_list {
+            for key in action.keys() {
+                if key.data.get_i64(SHOT_KEY_FIELD) == Some(projectile_id as i64) {
+                    spawns.push(ProjectileSpawn {
+                        action_id: action.info.action_data.action_id,
+                        frame: key.key_data.key_start_frame,
+                    });
+                }
+            }
+        }
+        spawns
+    }
+
+    fn projectile_boxes(&self, projectile: &RSZData) -> Vec<ProjectileBoxSize> {
+        let box_ids: Vec<u32> = match projectile.find_field(BOX_LIST_FIELD).map(|field| &field.value) {
+            Some(RSZValue::List(values)) => values.iter().filter_map(|value| value.as_i64()).map(|id| id as u32).collect(),
+            Some(value) => value.as_i64().map(|id| vec![id as u32]).unwrap_or_default(),
+            None => vec![],
+        };
+        let box_table = match self.data_table(DataId::ProjectileBox) {
+            Some(box_table) => box_table,
+            None => return vec![],
+        };
+        box_ids.iter().filter_map(|id| {
+            let rect = box_table.entry(*id)?.find_field(BOX_RECT_FIELD)?.value.components()?;
+            match rect[..] {
+                [x, y, width, height] => Some(ProjectileBoxSize {
+                    id: *id,
+                    x,
+                    y,
+                    width,
+                    height,
+                }),
+                _ => None,
+            }
+        }).collect()
+    }
+
+    pub fn projectile_summary(&self, projectile_id: u32) -> Option<ProjectileSummary> {
+        let projectile = self.data_table(DataId::ProjectileData)?.entry(projectile_id)?;
+        Some(ProjectileSummary {
+            id: projectile_id,
+            speed: projectile.find_field(SPEED_FIELD)
+                .and_then(|field| field.value.components())
+                .unwrap_or_default(),
+            lifetime: projectile.get_i64(LIFETIME_FIELD),
+            hits: projectile.get_i64(HIT_COUNT_FIELD),
+            boxes: self.projectile_boxes(projectile),
+            spawned_by: self.projectile_spawns(projectile_id),
+        })
+    }
+
+    pub fn projectile_summaries(&self) -> Vec<ProjectileSummary> {
+        match self.data_table(DataId::ProjectileData) {
+            Some(table) => table.data_ids.iter().filter_map(|id| self.projectile_summary(*id)).collect(),
+            None => vec![],
+        }
+    }
+}
+
+pub fn projectiles_to_csv(projectiles: &[ProjectileSummary]) -> String {
+    let rows = projectiles.iter().map(|projectile| vec![
+        projectile.id.to_string(),
+        projectile.speed.iter().map(|speed| speed.to_string()).collect::<Vec<String>>().join(" "),
+        projectile.lifetime.map(|lifetime| lifetime.to_string()).unwrap_or_default(),
+        projectile.hits.map(|hits| hits.to_string()).unwrap_or_default(),
+        projectile.boxes.iter()
+            .map(|box_size| format!("{}x{}@({}, {})", box_size.width, box_size.height, box_size.x, box_size.y))
+            .collect::<Vec<String>>()
+            .join(" "),
+        projectile.spawned_by.iter()
+            .map(|spawn| format!("{}@{}", spawn.action_id, spawn.frame))
+            .collect::<Vec<String>>()
+            .join(" "),
+    ]).collect();
+    write_csv(&["id", "speed", "lifetime", "hits", "boxes", "spawned_by"], &rows)
+}
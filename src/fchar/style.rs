@@ -0,0 +1,162 @@
+use serde::Serialize;
+
+use crate::fchar::CharacterAsset;
+use crate::forest::{build_forest, parent_indices};
+use crate::rsz::{RSZ, RSZData, RSZValue};
+
+#[derive(Serialize)]
+pub struct StyleNode<'a> {
+    pub id: i32,
+    pub parent_id: i32,
+    pub rsz: &'a RSZ,
+    pub children: Vec<StyleNode<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct EffectiveStyle {
+    pub id: i32,
+    //style ids from the requested style up to the root
+    pub chain: Vec<i32>,
+    pub data: Vec<RSZData>,
+}
+
+#[derive(Serialize)]
+pub struct FieldOverride {
+    pub class: String,
+    //which instance of the class, when a style holds several
+    pub occurrence: usize,
+    pub field: String,
+    pub inherited: Option<RSZValue>,
+    pub value: RSZValue,
+}
+
+#[derive(Serialize)]
+pub struct StyleDelta {
+    pub id: i32,
+    pub parent_id: Option<i32>,
+    pub overrides: Vec<FieldOverride>,
+}
+
+//Lists the fields of the layered data that differ from the data it was layered over
+pub fn diff_rsz_data(base: &[RSZData], layered: &[RSZData]) -> Vec<FieldOverride> {
+    let mut overrides: Vec<FieldOverride> = vec![];
+    for (n, data) in layered.iter().enumerate() {
+        let occurrence = layered[..n].iter().filter(|other| other.name == data.name).count();
+        let base_data = base.iter().filter(|other| other.name == data.name).nth(occurrence);
+        for field in &data.fields {
+            let inherited = base_data.and_then(|base_data| base_data.fields.iter().find(|other| other.name == field.name));
+            if inherited.map(|inherited| inherited.value != field.value).unwrap_or(true) {
+                overrides.push(FieldOverride {
+                    class: data.name.clone(),
+                    occurrence,
+                    field: field.name.clone(),
+                    inherited: inherited.map(|inherited| inherited.value.clone()),
+                    value: field.value.clone(),
+                });
+            }
+        }
+    }
+    overrides
+}
+
+//Styles store whole instances, so every field the layer stores replaces the base's, even one set back to the
+//default style's value. Fields the layer doesn't store are kept from the base
+pub fn merge_rsz_data(base: &mut Vec<RSZData>, layer: &[RSZData]) {
+    for (n, data) in layer.iter().enumerate() {
+        let occurrence = layer[..n].iter().filter(|other| other.name == data.name).count();
+        let base_data = match base.iter_mut().filter(|other| other.name == data.name).nth(occurrence) {
+            Some(base_data) => base_data,
+            None => {
+                base.push(data.clone());
+                continue;
+            }
+        };
+        for field in &data.fields {
+            match base_data.fields.iter_mut().find(|other| other.name == field.name) {
+                Some(base_field) => base_field.value = field.value.clone(),
+                None => base_data.fields.push(field.clone()),
+            }
+        }
+    }
+}
+
+impl CharacterAsset {
+    //Style 0 is the default style, the remaining ones are stored in style_data
+    pub fn style_rsz(&self, index: usize) -> Option<&RSZ> {
+        match index {
+            0 => Some(&self.default_style_data),
+            _ => self.style_data.get(index - 1).map(|style| &style.rsz),
+        }
+    }
+
+    pub fn style_index(&self, id: i32) -> Option<usize> {
+        self.id_table.iter().position(|style_id| *style_id == id)
+    }
+
+    fn parent_style_index(&self, index: usize) -> Option<usize> {
+        let parent_id = *self.parent_id_table.get(index)?;
+        match self.style_index(parent_id) {
+            Some(parent_index) if parent_index != index => Some(parent_index),
+            _ => None,
+        }
+    }
+
+    pub fn style_tree(&self) -> Vec<StyleNode<'_>> {
+        let parents = parent_indices(&self.id_table, |index| self.parent_id_table.get(index).copied());
+        build_forest(&parents, |index, children| {
+            Some(StyleNode {
+                id: self.id_table[index],
+                parent_id: *self.parent_id_table.get(index).unwrap_or(&-1),
+                rsz: self.style_rsz(index)?,
+                children,
+            })
+        })
+    }
+
+    //Layers the style over each of its parents and finally over the default style data
+    pub fn effective_style(&self, id: i32) -> Option<EffectiveStyle> {
+        let mut index = self.style_index(id)?;
+        let mut chain_indices: Vec<usize> = vec![index];
+        while let Some(parent_index) = self.parent_style_index(index) {
+            if chain_indices.contains(&parent_index) {
+                break;
+            }
+            chain_indices.push(parent_index);
+            index = parent_index;
+        }
+        let mut data = self.default_style_data.data.clone();
+        for index in chain_indices.iter().rev() {
+            if *index != 0 {
+                merge_rsz_data(&mut data, &self.style_rsz(*index)?.data);
+            }
+        }
+        Some(EffectiveStyle {
+            id,
+            chain: chain_indices.iter().map(|index| self.id_table[*index]).collect(),
+            data,
+        })
+    }
+
+    pub fn effective_styles(&self) -> Vec<EffectiveStyle> {
+        self.id_table.iter().filter_map(|id| self.effective_style(*id)).collect()
+    }
+
+    //Only the fields a style changes compared to its parent, or to the default style for root styles
+    pub fn style_delta(&self, id: i32) -> Option<StyleDelta> {
+        let effective = self.effective_style(id)?;
+        let parent_id = effective.chain.get(1).cloned();
+        let overrides = match parent_id {
+            Some(parent_id) => diff_rsz_data(&self.effective_style(parent_id)?.data, &effective.data),
+            None => diff_rsz_data(&self.default_style_data.data, &effective.data),
+        };
+        Some(StyleDelta {
+            id,
+            parent_id,
+            overrides,
+        })
+    }
+
+    pub fn style_deltas(&self) -> Vec<StyleDelta> {
+        self.id_table.iter().filter_map(|id| self.style_delta(*id)).collect()
+    }
+}
@@ -0,0 +1,103 @@
+use serde::Serialize;
+
+use crate::fchar::{ActionList, CharacterAsset, KeyData, Object};
+use crate::fchar::csv::write_csv;
+use crate::rsz::{RSZData, RSZField};
+
+pub struct ActionKey<'a> {
+    pub object_index: usize,
+    pub key_index: usize,
+    pub key_data: &'a KeyData,
+    pub data: &'a RSZData,
+}
+
+#[derive(Serialize)]
+pub struct TimelineRow<'a> {
+    pub action_id: i32,
+    pub object_index: usize,
+    pub key_index: usize,
+    pub key_class: &'a str,
+    pub start_frame: i32,
+    pub end_frame: i32,
+    pub fields: &'a [RSZField],
+}
+
+impl Object {
+    //Key i is described by the root instance at object_table[i]. When the key and root counts differ the pairing
+    //can't be trusted, so no key gets an instance
+    pub fn key_instance(&self, key_index: usize) -> Option<&RSZData> {
+        if self.action.object_table.len() != self.info.object_data.key_data.len() {
+            return None;
+        }
+        self.action.instance_data(*self.action.object_table.get(key_index)?)
+    }
+}
+
+impl ActionList {
+    //Key ranges paired with the root instance describing them, keys without one are left out
+    pub fn keys(&self) -> Vec<ActionKey<'_>> {
+        let mut keys: Vec<ActionKey> = vec![];
+        for (object_index, object) in self.objects.iter().enumerate() {
+            for (key_index, key_data) in object.info.object_data.key_data.iter().enumerate() {
+                if let Some(data) = object.key_instance(key_index) {
+                    keys.push(ActionKey {
+                        object_index,
+                        key_index,
+                        key_data,
+                        data,
+                    });
+                }
+            }
+        }
+        keys
+    }
+
+    //Each key range of an object is described by the matching root instance of the object's RSZ
+    pub fn timeline(&self) -> Vec<TimelineRow<'_>> {
+        let mut rows: Vec<TimelineRow> = vec![];
+        for (object_index, object) in self.objects.iter().enumerate() {
+            for (key_index, key_data) in object.info.object_data.key_data.iter().enumerate() {
+                let (key_class, fields) = match object.key_instance(key_index) {
+                    Some(key) => (key.name.as_str(), &key.fields[..]),
+                    None => ("", &[][..]),
+                };
+                rows.push(TimelineRow {
+                    action_id: self.info.action_data.action_id,
+                    object_index,
+                    key_index,
+                    key_class,
+                    start_frame: key_data.key_start_frame,
+                    end_frame: key_data.key_end_frame,
+                    fields,
+                });
+            }
+        }
+        rows
+    }
+}
+
+impl CharacterAsset {
+    pub fn action(&self, action_id: i32) -> Option<&ActionList> {
+        self.action_list.iter().find(|action| action.info.action_data.action_id == action_id)
+    }
+
+    pub fn timeline(&self) -> Vec<TimelineRow<'_>> {
+        self.action_list.iter().flat_map(|action| action.timeline()).collect()
+    }
+}
+
+pub fn timeline_to_csv(rows: &[TimelineRow]) -> String {
+    let csv_rows = rows.iter().map(|row| vec![
+        row.action_id.to_string(),
+        row.object_index.to_string(),
+        row.key_index.to_string(),
+        row.key_class.to_string(),
+        row.start_frame.to_string(),
+        row.end_frame.to_string(),
+        row.fields.iter()
+            .map(|field| format!("{}={}", field.name, field.value))
+            .collect::<Vec<String>>()
+            .join("; "),
+    ]).collect();
+    write_csv(&["action_id", "object_index", "key_index", "key_class", "start_frame", "end_frame", "fields"], &csv_rows)
+}
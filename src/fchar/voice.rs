@@ -0,0 +1,82 @@
+use serde::Serialize;
+
+use crate::fchar::{ActionList, CharacterAsset, DataId};
+use crate::fchar::csv::write_csv;
+use crate::fchar::fields::{FACIAL_FIELD, VOICE_FACIAL_KEY_FIELD, VOICE_FIELD};
+
+//Japanese and English voice tracks share the key's index
+pub const VOICE_TABLES: [DataId; 2] = [DataId::VoiceFacialData, DataId::VoiceFacialDataEN];
+
+#[derive(Serialize)]
+pub struct VoiceFacial {
+    pub action_id: i32,
+    pub table: DataId,
+    pub entry_id: u32,
+    pub voice_id: Option<i64>,
+    pub facial_id: Option<i64>,
+    pub frame: i32,
+}
+
+impl CharacterAsset {
+    fn list_voice_facials(&self, action: &ActionList) -> Vec<VoiceFacial> {
+        let action_id = action.info.action_data.action_id;
+        let mut voice_facials: Vec<VoiceFacial> = vec![];
+        for key in action.keys() {
+            let entry_id = match key.data.get_i64(VOICE_FACIAL_KEY_FIELD).filter(|id| *id >= 0) {
+                Some(entry_id) => entry_id as u32,
+                None => continue,
+            };
+            for table in VOICE_TABLES {
+                if let Some(entry) = self.data_table(table).and_then(|data_list| data_list.entry(entry_id)) {
+                    voice_facials.push(VoiceFacial {
+                        action_id,
+                        table,
+                        entry_id,
+                        voice_id: entry.get_i64(VOICE_FIELD),
+                        facial_id: entry.get_i64(FACIAL_FIELD),
+                        frame: key.key_data.key_start_frame,
+                    });
+                }
+            }
+        }
+        voice_facials.sort_by_key(|voice_facial| voice_facial.frame);
+        voice_facials
+    }
+
+    //Every action list entry with the id is included, ids aren't guaranteed to be unique
+    pub fn action_voice_facials(&self, action_id: i32) -> Vec<VoiceFacial> {
+        self.action_list.iter()
+            .filter(|action| action.info.action_data.action_id == action_id)
+            .flat_map(|action| self.list_voice_facials(action))
+            .collect()
+    }
+
+    pub fn voice_facials(&self) -> Vec<VoiceFacial> {
+        self.action_list.iter()
+            .flat_map(|action| self.list_voice_facials(action))
+            .collect()
+    }
+
+    //Leaves a value untouched when None is passed, returns false if the entry or a field is missing
+    pub fn set_voice_facial(&mut self, table: DataId, entry_id: u32, voice_id: Option<i64>, facial_id: Option<i64>) -> bool {
+        let entry = match self.data_table_mut(table).and_then(|data_list| data_list.entry_mut(entry_id)) {
+            Some(entry) => entry,
+            None => return false,
+        };
+        let voice_set = voice_id.map(|voice_id| entry.set_i64(VOICE_FIELD, voice_id)).unwrap_or(true);
+        let facial_set = facial_id.map(|facial_id| entry.set_i64(FACIAL_FIELD, facial_id)).unwrap_or(true);
+        voice_set && facial_set
+    }
+}
+
+pub fn voice_facials_to_csv(voice_facials: &[VoiceFacial]) -> String {
+    let rows = voice_facials.iter().map(|voice_facial| vec![
+        voice_facial.action_id.to_string(),
+        format!("{:?}", voice_facial.table),
+        voice_facial.entry_id.to_string(),
+        voice_facial.voice_id.map(|voice_id| voice_id.to_string()).unwrap_or_default(),
+        voice_facial.facial_id.map(|facial_id| facial_id.to_string()).unwrap_or_default(),
+        voice_facial.frame.to_string(),
+    ]).collect();
+    write_csv(&["action_id", "table", "entry_id", "voice_id", "facial_id", "frame"], &rows)
+}
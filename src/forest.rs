@@ -0,0 +1,49 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+//Index of each item's parent, looked up by id through a map built once; self parents and unknown ids are roots
+pub(crate) fn parent_indices<T: Eq + Hash + Copy>(ids: &[T], parent_ids: impl Fn(usize) -> Option<T>) -> Vec<Option<usize>> {
+    let mut index_of: HashMap<T, usize> = HashMap::new();
+    for (index, id) in ids.iter().enumerate() {
+        index_of.entry(*id).or_insert(index);
+    }
+    (0..ids.len())
+        .map(|index| parent_ids(index)
+            .and_then(|parent_id| index_of.get(&parent_id).copied())
+            .filter(|parent_index| *parent_index != index))
+        .collect()
+}
+
+//Builds nested nodes out of a parent index per item, node returns None for items to leave out along with their children
+pub(crate) fn build_forest<T>(parents: &[Option<usize>], mut node: impl FnMut(usize, Vec<T>) -> Option<T>) -> Vec<T> {
+    let mut children: Vec<Vec<usize>> = vec![vec![]; parents.len()];
+    for (index, parent) in parents.iter().enumerate() {
+        if let Some(parent) = parent.filter(|parent| *parent < parents.len()) {
+            children[parent].push(index);
+        }
+    }
+    let mut visited: HashSet<usize> = HashSet::new();
+    let mut roots: Vec<T> = vec![];
+    let root_indices = (0..parents.len()).filter(|index| parents[*index].is_none());
+    //items caught in a parent cycle never reach a root, list them at the top level
+    for index in root_indices.chain(0..parents.len()) {
+        if visited.insert(index) {
+            if let Some(root) = forest_node(index, &children, &mut visited, &mut node) {
+                roots.push(root);
+            }
+        }
+    }
+    roots
+}
+
+fn forest_node<T>(index: usize, children: &[Vec<usize>], visited: &mut HashSet<usize>, node: &mut impl FnMut(usize, Vec<T>) -> Option<T>) -> Option<T> {
+    let mut child_nodes: Vec<T> = vec![];
+    for child in &children[index] {
+        if visited.insert(*child) {
+            if let Some(child_node) = forest_node(*child, children, visited, node) {
+                child_nodes.push(child_node);
+            }
+        }
+    }
+    node(index, child_nodes)
+}
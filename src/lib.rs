@@ -1,15 +1,51 @@
+use std::path::Path;
 use nom::IResult;
+use crate::detect::FileType;
 use crate::fchar::CharacterAsset;
 use crate::prefab::Prefab;
+use crate::profile::GameProfile;
+use crate::scene::Scene;
+use crate::schema::SchemaSource;
+use crate::user::UserData;
 
+pub mod detect;
 pub mod rsz;
 pub mod fchar;
+mod forest;
 pub mod prefab;
+pub mod profile;
+pub mod scene;
+pub mod schema;
+pub mod user;
 
-pub fn parse_fchar(input: &[u8]) -> IResult<&[u8], CharacterAsset> {
-    fchar::parse_fchar(input)
+pub fn parse_fchar<'a>(input: &'a [u8], profile: &GameProfile) -> IResult<&'a [u8], CharacterAsset> {
+    fchar::parse_fchar(input, profile)
 }
 
-pub fn parse_prefab(input: &[u8]) -> IResult<&[u8], Prefab> {
-    prefab::parse_prefab(input)
-}
\ No newline at end of file
+pub fn parse_prefab<'a>(input: &'a [u8], profile: &GameProfile) -> IResult<&'a [u8], Prefab> {
+    prefab::parse_prefab(input, profile)
+}
+
+pub fn parse_scene<'a>(input: &'a [u8], profile: &GameProfile) -> IResult<&'a [u8], Scene> {
+    scene::parse_scene(input, profile)
+}
+
+pub fn detect(input: &[u8], file_name: &str) -> Option<FileType> {
+    detect::detect(input, file_name)
+}
+
+pub fn parse_user<'a>(input: &'a [u8], profile: &GameProfile) -> IResult<&'a [u8], UserData> {
+    user::parse_user(input, profile)
+}
+
+//Picks the profile from the magic, header layout and file name, unknown versions get the SF6 profile
+pub fn detect_profile(input: &[u8], file_name: &str) -> &'static GameProfile {
+    detect::detect(input, file_name)
+        .map(|file_type| file_type.profile())
+        .unwrap_or(&profile::SF6)
+}
+
+//Loads the RSZ dump the parsers read classes from
+pub fn load_schema_file(path: &Path) -> Result<SchemaSource, Box<dyn std::error::Error>> {
+    schema::load_schema_file(path)
+}
@@ -1,61 +1,133 @@
 use std::env;
 use std::fs::File;
 use std::io::{BufReader, Read};
-use serde::{Deserialize, Serialize};
-use crate::rsz::json_parser::parse_json;
-use include_bytes_zstd::include_bytes_zstd;
-
-mod fchar;
-mod rsz;
-mod prefab;
-
-#[derive(Serialize, Deserialize)]
-struct UserData {
-    magic: u32,
-    resource_count: i32,
-    userdata_count: i32,
-    info_count: i32,
-    resource_info_tbl: u64,
-    userdata_info_tbl: u64,
-    data_offset: u64,
+use std::path::{Path, PathBuf};
+
+use sf6_rsz_parser::{detect, fchar, prefab, profile, rsz, scene, schema, user};
+
+fn write_export(file_name: &str, suffix: &str, contents: String) -> std::io::Result<()> {
+    let mut export_name = file_name.to_string();
+    export_name.push_str(suffix);
+    std::fs::write(export_name, contents)
+}
+
+#[cfg(feature = "embedded-schema")]
+fn embedded_schema(game: profile::Game) -> Option<Vec<u8>> {
+    use include_bytes_zstd::include_bytes_zstd;
+
+    match game {
+        profile::Game::SF6 => Some(include_bytes_zstd!("rszsf6.json", 9)),
+        profile::Game::DMC5 => Some(include_bytes_zstd!("rszdmc5.json", 9)),
+    }
 }
 
-#[derive(Serialize, Deserialize)]
-struct StandardGameObjectInfo {
-    guid: rsz::GUID,
-    id: i32,
-    parent_id: i32,
-    component_count: u16,
-    unknown: i16,
-    prefab_id: i32,
+#[cfg(not(feature = "embedded-schema"))]
+fn embedded_schema(_game: profile::Game) -> Option<Vec<u8>> {
+    None
 }
 
-#[derive(Serialize, Deserialize)]
-struct UserDataInfo {
-    crc: u32,
+//An external dump always wins over the one built in for the game, any failure ends the run
+fn load_schema(profile: &profile::GameProfile, path: Option<&Path>) {
+    let loaded = match path {
+        Some(path) => schema::load_schema_file(path),
+        None => match embedded_schema(profile.game) {
+            Some(json_bytes) => schema::load_schema_bytes(profile, json_bytes),
+            None => Err(schema::SchemaError(format!("no schema is built in for {:?}, pass the dump with --schema", profile.game)).into()),
+        },
+    };
+    match loaded {
+        Ok(schema) => println!("Using schema {}", schema),
+        Err(error) => {
+            println!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+//The fchar exports look fields up by name, a dump renaming one leaves its column empty instead of failing
+fn warn_missing_fields() {
+    let missing = fchar::missing_schema_fields();
+    if !missing.is_empty() {
+        println!("Fields not found in the schema: {}", missing.join(", "));
+    }
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    //--schema <path> reads the RSZ dump from a file instead of the one built in, e.g. after a game patch
+    let schema_path = match args.iter().position(|arg| arg == "--schema") {
+        Some(index) if index + 1 < args.len() => {
+            let path = PathBuf::from(args.remove(index + 1));
+            args.remove(index);
+            Some(path)
+        }
+        Some(_) => {
+            println!("--schema needs the path of an RSZ dump!");
+            std::process::exit(1);
+        }
+        None => None,
+    };
+    if let Some(path) = schema_path.as_ref().filter(|path| !path.is_file()) {
+        println!("Schema file {} does not exist!", path.display());
+        std::process::exit(1);
+    }
     if args.len() <= 1 {
         println!("\nArguments not provided! The argument should be the file to parse.")
     }
 
-    //check file ending to determine if file from SF6 or DMC5
-    let json_bytes = match args[1].ends_with(".17"){
-        true=>include_bytes_zstd!("rszsf6.json", 9),
-        false=>include_bytes_zstd!("rszdmc5.json", 9),
-    };
-    //sets up the parser to parse RSZ's from the selected game
-    parse_json(json_bytes)?;
-    
+    let is_dir = std::path::Path::new(&args[1]).is_dir();
+
+    if is_dir {
+        let dir = std::path::Path::new(&args[1]);
+        //the first fchar file decides the game, files from other games are skipped
+        let profile = match fchar::personal::folder_profile(dir)? {
+            Some(profile) => profile,
+            None => {
+                println!("No fchar files found in {}!", dir.display());
+                return Ok(());
+            }
+        };
+        load_schema(profile, schema_path.as_deref());
+        warn_missing_fields();
+        println!("Comparing personal data of fchar files...");
+        let comparison = fchar::personal::compare_personal_data(dir, profile)?;
+        for skipped in &comparison.skipped {
+            println!("Skipping {}, {}!", skipped.file_name, skipped.reason);
+        }
+        let characters = comparison.characters;
+        std::fs::write(dir.join("personal_data.csv"), fchar::personal::personal_data_to_csv(&characters))?;
+        std::fs::write(dir.join("personal_data.json"), serde_json::to_string_pretty(&characters).unwrap())?;
+        println!("Complete!");
+        return Ok(());
+    }
+
     let mut reader = BufReader::with_capacity(0x7fffff,File::open(&args[1]).unwrap());
     let mut buffer: Vec<u8> = vec![];
     reader.read_to_end(&mut buffer).unwrap();
-    
-    if args[1].ends_with("fchar.17")
+
+    let file_type = match detect::detect(&buffer, &args[1]) {
+        Some(file_type) => file_type,
+        None => {
+            println!("Could not tell what kind of file {} is!", args[1]);
+            return Ok(());
+        }
+    };
+    //the magic and header layout determine which game the file is from
+    let profile = file_type.profile();
+    //sets up the parser to parse RSZ's from the selected game
+    load_schema(profile, schema_path.as_deref());
+
+    if file_type.kind == detect::FileKind::CharacterAsset
     {
-        let fchar_file = fchar::parse_fchar(&buffer).unwrap().1;
+        warn_missing_fields();
+        println!("Parsing fchar file...");
+        let mut fchar_file = match fchar::parse_fchar(&buffer, profile) {
+            Ok((_, fchar_file)) => fchar_file,
+            Err(error) => {
+                println!("Could not parse {}: {}", args[1], error);
+                return Ok(());
+            }
+        };
         let serialized_fchar = serde_json::to_string_pretty(&fchar_file).unwrap();
         println!("Writing fchar to json...");
 
@@ -63,12 +135,120 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         json_name.push_str(".json");
 
         std::fs::write(json_name, serialized_fchar)?;
+
+        //an optional second argument picks an extra export
+        match args.get(2).map(|export| export.as_str()) {
+            Some("styles") => {
+                println!("Writing style tree to json...");
+                let serialized_styles = serde_json::to_string_pretty(&serde_json::json!({
+                    "tree": fchar_file.style_tree(),
+                    "effective": fchar_file.effective_styles(),
+                    "deltas": fchar_file.style_deltas(),
+                })).unwrap();
+                write_export(&args[1], ".styles.json", serialized_styles)?;
+            }
+            Some("timeline") => {
+                println!("Writing action timeline to csv and json...");
+                let timeline = fchar_file.timeline();
+                write_export(&args[1], ".timeline.csv", fchar::timeline::timeline_to_csv(&timeline))?;
+                write_export(&args[1], ".timeline.json", serde_json::to_string_pretty(&timeline).unwrap())?;
+            }
+            Some("cancels") => {
+                println!("Writing cancel graph to dot and json...");
+                let cancel_graph = fchar_file.cancel_graph();
+                write_export(&args[1], ".cancels.dot", cancel_graph.to_dot())?;
+                write_export(&args[1], ".cancels.json", serde_json::to_string_pretty(&cancel_graph.adjacency()).unwrap())?;
+            }
+            Some("commands") => {
+                println!("Writing decoded commands to json...");
+                let serialized_commands = serde_json::to_string_pretty(&serde_json::json!({
+                    "commands": fchar_file.decode_commands(),
+                    "groups": fchar_file.decode_command_groups(),
+                })).unwrap();
+                write_export(&args[1], ".commands.json", serialized_commands)?;
+            }
+            Some("attacks") => {
+                println!("Writing attack data to csv and json...");
+                let attack_data = fchar_file.attack_data();
+                write_export(&args[1], ".attacks.csv", fchar::attack::attack_data_to_csv(&attack_data))?;
+                write_export(&args[1], ".attacks.json", serde_json::to_string_pretty(&attack_data).unwrap())?;
+            }
+            Some("projectiles") => {
+                println!("Writing projectile summaries to csv and json...");
+                let projectiles = fchar_file.projectile_summaries();
+                write_export(&args[1], ".projectiles.csv", fchar::projectile::projectiles_to_csv(&projectiles))?;
+                write_export(&args[1], ".projectiles.json", serde_json::to_string_pretty(&projectiles).unwrap())?;
+            }
+            Some("voices") => {
+                println!("Writing voice and facial data to csv and json...");
+                let voice_facials = fchar_file.voice_facials();
+                write_export(&args[1], ".voices.csv", fchar::voice::voice_facials_to_csv(&voice_facials))?;
+                write_export(&args[1], ".voices.json", serde_json::to_string_pretty(&voice_facials).unwrap())?;
+            }
+            Some("cameras") => {
+                println!("Writing camera and screen vibration data to json...");
+                write_export(&args[1], ".cameras.json", serde_json::to_string_pretty(&fchar_file.action_cameras()).unwrap())?;
+            }
+            Some("curves") => {
+                println!("Writing owner and target curves to csv and svg...");
+                let curves = fchar_file.sampled_curves();
+                write_export(&args[1], ".curves.csv", fchar::curve::curves_to_csv(&curves))?;
+                write_export(&args[1], ".curves.svg", fchar::curve::curves_to_svg(&curves))?;
+            }
+            Some("personal") => {
+                println!("Writing personal data to json...");
+                write_export(&args[1], ".personal.json", serde_json::to_string_pretty(&fchar_file.personal_parameters()).unwrap())?;
+            }
+            Some("combo") => {
+                let combo = args.get(3).map(|combo| combo.as_str()).unwrap_or_default();
+                //an optional json file replaces the built-in SF6 scaling values it lists
+                let rules = match args.get(4) {
+                    Some(rules_path) => fchar::combo::ScalingRules::from_json(&std::fs::read_to_string(rules_path)?)?,
+                    None => fchar::combo::ScalingRules::sf6(),
+                };
+                let result = fchar_file.parse_combo(combo, &rules)?;
+                for hit in &result.hits {
+                    println!("{} (frame {}, {}%): {} damage, {} stun, {} drive", hit.action_id, hit.frame, hit.scaling, hit.damage, hit.stun, hit.drive);
+                }
+                println!("Total: {} damage, {} stun, {} drive", result.damage, result.stun, result.drive);
+                write_export(&args[1], ".combo.json", serde_json::to_string_pretty(&result).unwrap())?;
+            }
+            Some("diff") => {
+                //the file passed first is the older patch
+                let new_name = args.get(3).ok_or("No newer fchar file to compare against!")?;
+                let new_buffer = std::fs::read(new_name)?;
+                let new_fchar_file = match fchar::parse_fchar(&new_buffer, profile) {
+                    Ok((_, new_fchar_file)) => new_fchar_file,
+                    Err(error) => {
+                        println!("Could not parse {}: {}", new_name, error);
+                        return Ok(());
+                    }
+                };
+                println!("Writing patch diff to markdown and json...");
+                let diff = fchar_file.patch_diff(&new_fchar_file);
+                write_export(&args[1], ".diff.md", diff.to_markdown())?;
+                write_export(&args[1], ".diff.json", serde_json::to_string_pretty(&diff).unwrap())?;
+            }
+            Some("encode") => {
+                //replaces the inputs of one command and writes the file back out
+                let command_id = args.get(3).and_then(|command_id| command_id.parse::<u32>().ok()).ok_or("No command id to encode!")?;
+                let notation = args.get(4).ok_or("No notation to encode!")?;
+                fchar_file.encode_command(command_id, notation)?;
+                println!("Rebuilding fchar with command {} as {}...", command_id, notation);
+                std::fs::write(format!("{}.rebuilt", args[1]), fchar::write_fchar(&fchar_file, profile))?;
+            }
+            Some("rebuild") => {
+                println!("Rebuilding fchar...");
+                std::fs::write(format!("{}.rebuilt", args[1]), fchar::write_fchar(&fchar_file, profile))?;
+            }
+            Some(export) => println!("Unknown export {}!", export),
+            None => {}
+        }
         println!("Complete!");
     }
-    //From what I can tell this is basically how alpha checks if the file is from an older re game or if it's from a newer one. 
-    else if args[1].ends_with("pfb.17") || args[1].ends_with("pfb.16")
+    else if file_type.kind == detect::FileKind::Prefab
     {
-        let pfb_file = prefab::parse_prefab(&buffer,args[1].ends_with("pfb.16")).unwrap().1;
+        let pfb_file = prefab::parse_prefab(&buffer, profile).unwrap().1;
         let serialized_prefab = serde_json::to_string_pretty(&pfb_file).unwrap();
         
         println!("Writing prefab to json...");
@@ -77,8 +257,83 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         json_name.push_str(".json");
 
         std::fs::write(json_name, serialized_prefab)?;
+
+        match args.get(2).map(|export| export.as_str()) {
+            Some("tree") => {
+                println!("Writing GameObject hierarchy to json...");
+                write_export(&args[1], ".tree.json", serde_json::to_string_pretty(&pfb_file.tree()).unwrap())?;
+            }
+            Some("links") => {
+                println!("Writing resolved GameObjectRef links to json...");
+                write_export(&args[1], ".links.json", serde_json::to_string_pretty(&pfb_file.resolved_links()).unwrap())?;
+            }
+            Some("rebuild") => {
+                println!("Rebuilding prefab...");
+                std::fs::write(format!("{}.rebuilt", args[1]), prefab::write_prefab(&pfb_file, profile))?;
+            }
+            Some(export) => println!("Unknown export {}!", export),
+            None => {}
+        }
+        println!("Complete!");
+    }
+    else if file_type.kind == detect::FileKind::UserData
+    {
+        let user_file = user::parse_user(&buffer, profile).unwrap().1;
+        println!("Writing user data to json...");
+        write_export(&args[1], ".json", serde_json::to_string_pretty(&user_file).unwrap())?;
+
+        match args.get(2).map(|export| export.as_str()) {
+            //writes the parsed file back out, to check that it survives a round trip
+            Some("rebuild") => {
+                println!("Rebuilding user data...");
+                std::fs::write(format!("{}.rebuilt", args[1]), user::write_user(&user_file, profile))?;
+            }
+            Some(export) => println!("Unknown export {}!", export),
+            None => {}
+        }
         println!("Complete!");
     }
+    else if file_type.kind == detect::FileKind::Scene
+    {
+        let scn_file = scene::parse_scene(&buffer, profile).unwrap().1;
+        println!("Writing scene to json...");
+        write_export(&args[1], ".json", serde_json::to_string_pretty(&scn_file).unwrap())?;
+
+        match args.get(2).map(|export| export.as_str()) {
+            Some("tree") => {
+                println!("Writing GameObject hierarchy to json...");
+                write_export(&args[1], ".tree.json", serde_json::to_string_pretty(&scn_file.tree()).unwrap())?;
+            }
+            Some("rebuild") => {
+                println!("Rebuilding scene...");
+                std::fs::write(format!("{}.rebuilt", args[1]), scene::write_scene(&scn_file, profile))?;
+            }
+            Some(export) => println!("Unknown export {}!", export),
+            None => {}
+        }
+        println!("Complete!");
+    }
+    else if file_type.kind == detect::FileKind::Rsz
+    {
+        let rsz_block = rsz::parse_rsz(&buffer, 0).unwrap().1;
+        println!("Writing RSZ to json...");
+        write_export(&args[1], ".json", serde_json::to_string_pretty(&rsz_block).unwrap())?;
+
+        match args.get(2).map(|export| export.as_str()) {
+            Some("rebuild") => {
+                println!("Rebuilding RSZ...");
+                let mut bytes: Vec<u8> = vec![];
+                rsz::write_rsz(&rsz_block, &mut bytes);
+                std::fs::write(format!("{}.rebuilt", args[1]), bytes)?;
+            }
+            Some(export) => println!("Unknown export {}!", export),
+            None => {}
+        }
+        println!("Complete!");
+    }
+    else {
+        println!("{:?} files are not supported yet!", file_type.kind);
+    }
 
     Ok(())
 }
\ No newline at end of file
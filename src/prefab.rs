@@ -5,9 +5,14 @@
 };
 use nom::multi::count;
 use nom::number::complete::{le_i32, le_u32, le_u64};
+use std::io::Write;
 use serde::{Deserialize, Serialize};
 
-use crate::rsz::{parse_rsz, RSZ, GameObjectInfo, UserDataInfo, GameObjectRefInfo, ResourceInfo, parse_gobject_info, parse_gobject_ref_info, parse_resource_info, parse_userdata_info};
+use crate::profile::GameProfile;
+use crate::rsz::{parse_rsz, write_rsz, write_utf16, align_to, RSZ, GameObjectInfo, UserDataInfo, GameObjectRefInfo, ResourceInfo, parse_gobject_info, parse_gobject_ref_info, parse_resource_info, parse_userdata_info};
+
+pub mod reference;
+pub mod tree;
 
 #[derive(Serialize, Deserialize)]
 pub struct PrefabHeader {
@@ -119,15 +124,16 @@ pub struct Prefab {
     pub gameobject: RSZ,
 }
 
-pub fn parse_prefab(input: &[u8],is16version:bool) -> IResult<&[u8], Prefab> {
+pub fn parse_prefab<'a>(input: &'a [u8], profile: &GameProfile) -> IResult<&'a [u8], Prefab> {
     //sf5 has smaller header, skip some values when reading
-    let (remainder, header) = match is16version {
-        true=>parse_prefab_header_16(input).unwrap(),
-        false=>parse_prefab_header_17(input).unwrap(),
+    let (remainder, header) = match profile.prefab_userdata {
+        false=>parse_prefab_header_16(input).unwrap(),
+        true=>parse_prefab_header_17(input).unwrap(),
     };
+    let alignment = profile.table_alignment;
     let (remainder, gameobject_infos) = count(parse_gobject_info, header.info_count as usize)(remainder).unwrap();
     let (mut remainder, gameobject_ref_infos) = count(parse_gobject_ref_info, header.gameobject_ref_info_count as usize)(remainder).unwrap();
-    let alignment_remainder = (16 -(input.len() - remainder.len()) % 16) % 16;
+    let alignment_remainder = (alignment -(input.len() - remainder.len()) % alignment) % alignment;
     if alignment_remainder != 0 
     {
         remainder = &remainder[alignment_remainder..];
@@ -135,11 +141,11 @@ pub fn parse_prefab(input: &[u8],is16version:bool) -> IResult<&[u8], Prefab> {
     let mut resource_infos: Vec<ResourceInfo> = vec![];
     for _ in 0..header.resource_count {
         let offset = input.len() - remainder.len();
-        let (new_remainder, resource_info) = parse_resource_info(input, offset,is16version).unwrap();
+        let (new_remainder, resource_info) = parse_resource_info(input, offset, profile).unwrap();
         remainder = new_remainder;
         resource_infos.push(resource_info);
     }
-    let alignment_remainder = (16 -(input.len() - remainder.len()) % 16) % 16;
+    let alignment_remainder = (alignment -(input.len() - remainder.len()) % alignment) % alignment;
     if alignment_remainder != 0 {
         remainder = &remainder[alignment_remainder..];
     }
@@ -172,3 +178,76 @@ pub fn parse_prefab(input: &[u8],is16version:bool) -> IResult<&[u8], Prefab> {
         }
     ))
 }
+
+//Tables are laid out again from scratch, call sync_gameobject_ref_infos first if objects were added or removed
+pub fn write_prefab(prefab: &Prefab, profile: &GameProfile) -> Vec<u8> {
+    let header_size = match profile.prefab_userdata {
+        false => 40,
+        true => 56,
+    };
+    let mut bytes: Vec<u8> = vec![0; header_size];
+    for gameobject_info in &prefab.gameobject_infos {
+        bytes.write_all(&gameobject_info.id.to_le_bytes()).unwrap();
+        bytes.write_all(&gameobject_info.parent_id.to_le_bytes()).unwrap();
+        bytes.write_all(&gameobject_info.component_count.to_le_bytes()).unwrap();
+    }
+    let gameobject_ref_info_tbl = bytes.len() as u64;
+    for ref_info in &prefab.gameobject_ref_infos {
+        bytes.write_all(&ref_info.object_id.to_le_bytes()).unwrap();
+        bytes.write_all(&ref_info.property_id.to_le_bytes()).unwrap();
+        bytes.write_all(&ref_info.array_index.to_le_bytes()).unwrap();
+        bytes.write_all(&ref_info.target_id.to_le_bytes()).unwrap();
+    }
+    align_to(&mut bytes, profile.table_alignment);
+    let resource_info_tbl = bytes.len();
+    match profile.inline_resource_paths {
+        true => prefab.resource_infos.iter().for_each(|resource_info| write_utf16(&resource_info.string, &mut bytes)),
+        false => bytes.resize(resource_info_tbl + 8 * prefab.resource_infos.len(), 0),
+    }
+    align_to(&mut bytes, profile.table_alignment);
+    let userdata_info_tbl = bytes.len();
+    if profile.prefab_userdata {
+        bytes.resize(userdata_info_tbl + 16 * prefab.userdata_infos.len(), 0);
+    }
+
+    //the path strings follow the tables, the tables point at them with absolute offsets
+    if !profile.inline_resource_paths {
+        for (n, resource_info) in prefab.resource_infos.iter().enumerate() {
+            let str_offset = bytes.len() as u64;
+            write_utf16(&resource_info.string, &mut bytes);
+            let entry = resource_info_tbl + 8 * n;
+            bytes[entry..entry + 8].copy_from_slice(&str_offset.to_le_bytes());
+        }
+    }
+    if profile.prefab_userdata {
+        for (n, userdata_info) in prefab.userdata_infos.iter().enumerate() {
+            let str_offset = bytes.len() as u64;
+            write_utf16(&userdata_info.string, &mut bytes);
+            let entry = userdata_info_tbl + 16 * n;
+            bytes[entry..entry + 4].copy_from_slice(&userdata_info.instance_id.to_le_bytes());
+            bytes[entry + 4..entry + 8].copy_from_slice(&userdata_info.type_id.to_le_bytes());
+            bytes[entry + 8..entry + 16].copy_from_slice(&str_offset.to_le_bytes());
+        }
+    }
+    align_to(&mut bytes, profile.table_alignment);
+    let data_offset = bytes.len() as u64;
+    write_rsz(&prefab.gameobject, &mut bytes);
+
+    let mut header: Vec<u8> = vec![];
+    header.write_all(&prefab.header.magic.to_le_bytes()).unwrap();
+    header.write_all(&(prefab.gameobject_infos.len() as i32).to_le_bytes()).unwrap();
+    header.write_all(&(prefab.resource_infos.len() as i32).to_le_bytes()).unwrap();
+    header.write_all(&(prefab.gameobject_ref_infos.len() as i32).to_le_bytes()).unwrap();
+    if profile.prefab_userdata {
+        header.write_all(&(prefab.userdata_infos.len() as i32).to_le_bytes()).unwrap();
+        header.write_all(&prefab.header.reserved.unwrap_or(0).to_le_bytes()).unwrap();
+    }
+    header.write_all(&gameobject_ref_info_tbl.to_le_bytes()).unwrap();
+    header.write_all(&(resource_info_tbl as u64).to_le_bytes()).unwrap();
+    if profile.prefab_userdata {
+        header.write_all(&(userdata_info_tbl as u64).to_le_bytes()).unwrap();
+    }
+    header.write_all(&data_offset.to_le_bytes()).unwrap();
+    bytes[0..header_size].copy_from_slice(&header);
+    bytes
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use serde::Serialize;
+
+use crate::prefab::Prefab;
+use crate::rsz::{GameObjectRefInfo, GUID, RSZData, RSZValue};
+
+//A GameObjectRef field and the GameObject it points at, by instance id so it survives objects being added or removed
+#[derive(Serialize, Clone, PartialEq)]
+pub struct GameObjectLink {
+    pub source_instance: i32,
+    pub field: String,
+    //-1 when the field is not an array
+    pub array_index: i32,
+    pub guid: Option<GUID>,
+    pub target_instance: i32,
+}
+
+#[derive(Serialize)]
+pub struct ResolvedLink<'a> {
+    pub link: GameObjectLink,
+    pub source: Option<&'a RSZData>,
+    pub target: Option<&'a RSZData>,
+}
+
+fn field_guid(data: &RSZData, property_id: i32, array_index: i32) -> Option<GUID> {
+    let field = data.fields.get(usize::try_from(property_id).ok()?)?;
+    let value = match &field.value {
+        RSZValue::List(values) => values.get(usize::try_from(array_index).ok()?)?,
+        value => value,
+    };
+    match value {
+        RSZValue::GUID(guid) => Some(guid.clone()),
+        _ => None,
+    }
+}
+
+impl Prefab {
+    fn object_instance(&self, object_index: i32) -> Option<i32> {
+        self.gameobject.object_table.get(usize::try_from(object_index).ok()?).copied()
+    }
+
+    //object_id and target_id index the object table, property_id is the field's position in the instance
+    pub fn gameobject_links(&self) -> Vec<GameObjectLink> {
+        let mut links: Vec<GameObjectLink> = vec![];
+        for info in &self.gameobject_ref_infos {
+            let (source_instance, target_instance) = match (self.object_instance(info.object_id), self.object_instance(info.target_id)) {
+                (Some(source_instance), Some(target_instance)) => (source_instance, target_instance),
+                _ => continue,
+            };
+            let source = self.gameobject.instance_data(source_instance);
+            links.push(GameObjectLink {
+                source_instance,
+                field: source
+                    .and_then(|source| source.fields.get(info.property_id as usize))
+                    .map(|field| field.name.clone())
+                    .unwrap_or_default(),
+                array_index: info.array_index,
+                guid: source.and_then(|source| field_guid(source, info.property_id, info.array_index)),
+                target_instance,
+            });
+        }
+        links
+    }
+
+    pub fn resolved_links(&self) -> Vec<ResolvedLink<'_>> {
+        self.gameobject_links().into_iter()
+            .map(|link| ResolvedLink {
+                source: self.gameobject.instance_data(link.source_instance),
+                target: self.gameobject.instance_data(link.target_instance),
+                link,
+            })
+            .collect()
+    }
+
+    //Rebuilds the table from links, dropping those whose source, field or target no longer exists
+    pub fn sync_gameobject_ref_infos(&mut self, links: &[GameObjectLink]) {
+        let object_indices: HashMap<i32, i32> = self.gameobject.object_table.iter().enumerate()
+            .map(|(index, instance_id)| (*instance_id, index as i32))
+            .collect();
+        let mut ref_infos: Vec<GameObjectRefInfo> = vec![];
+        for link in links {
+            let property_id = self.gameobject.instance_data(link.source_instance)
+                .and_then(|source| source.fields.iter().position(|field| field.name == link.field));
+            let ids = (object_indices.get(&link.source_instance).copied(), property_id, object_indices.get(&link.target_instance).copied());
+            if let (Some(object_id), Some(property_id), Some(target_id)) = ids {
+                ref_infos.push(GameObjectRefInfo {
+                    object_id,
+                    property_id: property_id as i32,
+                    array_index: link.array_index,
+                    target_id,
+                });
+            }
+        }
+        self.header.gameobject_ref_info_count = ref_infos.len() as i32;
+        self.gameobject_ref_infos = ref_infos;
+    }
+}
@@ -0,0 +1,62 @@
+use serde::Serialize;
+
+use crate::forest::{build_forest, parent_indices};
+use crate::prefab::Prefab;
+use crate::rsz::{GameObjectInfo, RSZ, RSZData};
+
+#[derive(Serialize)]
+pub struct PrefabNode<'a> {
+    //index of the via.GameObject instance in the object table
+    pub id: i32,
+    pub parent_id: i32,
+    pub gameobject: Option<&'a RSZData>,
+    pub components: Vec<&'a RSZData>,
+    //path of the prefab a scene GameObject was spawned from, always None in prefabs
+    pub prefab: Option<&'a str>,
+    pub children: Vec<PrefabNode<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct PrefabTree<'a> {
+    pub roots: Vec<PrefabNode<'a>>,
+}
+
+//Objects are indexed through the object table, GameObjectInfo ids refer to it rather than to instances
+fn object_data(rsz: &RSZ, object_index: i32) -> Option<&RSZData> {
+    let instance_id = *rsz.object_table.get(usize::try_from(object_index).ok()?)?;
+    rsz.instance_data(instance_id)
+}
+
+//The components of a GameObject are the objects following it in the object table
+fn components<'a>(info: &GameObjectInfo, rsz: &'a RSZ) -> Vec<&'a RSZData> {
+    (1..=info.component_count)
+        .filter_map(|n| object_data(rsz, info.id + n))
+        .collect()
+}
+
+//Shared by prefabs and scenes, which both describe their GameObjects with id, parent and component count,
+//prefab_paths holds the prefab of each info in scenes and is left empty for prefabs
+pub fn gameobject_tree<'a>(infos: &[GameObjectInfo], prefab_paths: &[Option<&'a str>], rsz: &'a RSZ) -> PrefabTree<'a> {
+    let ids: Vec<i32> = infos.iter().map(|info| info.id).collect();
+    let parents = parent_indices(&ids, |index| Some(infos[index].parent_id));
+    let roots = build_forest(&parents, |index, children| {
+        let info = &infos[index];
+        Some(PrefabNode {
+            id: info.id,
+            parent_id: info.parent_id,
+            gameobject: object_data(rsz, info.id),
+            components: components(info, rsz),
+            prefab: prefab_paths.get(index).copied().flatten(),
+            children,
+        })
+    });
+    PrefabTree {
+        roots,
+    }
+}
+
+impl Prefab {
+    pub fn tree(&self) -> PrefabTree<'_> {
+        gameobject_tree(&self.gameobject_infos, &[], &self.gameobject)
+    }
+}
@@ -0,0 +1,70 @@
+use serde::Serialize;
+
+use crate::detect::FileKind;
+use crate::rsz::version::{DMC5_VERSION, SF6_VERSION};
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Game {
+    SF6,
+    DMC5,
+}
+
+//Everything that changes between RE Engine titles, passed to the parsers instead of per-file flags
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GameProfile {
+    pub game: Game,
+    //file name of the RSZ dump describing the game's classes, None when no dump is shipped for it
+    pub schema: Option<&'static str>,
+    pub fchar_version: Option<u32>,
+    pub pfb_version: u32,
+    pub scn_version: u32,
+    pub user_version: u32,
+    pub rsz_version: u32,
+    //older prefab headers have no userdata count or userdata table
+    pub prefab_userdata: bool,
+    //older files store resource paths inline instead of a table of offsets
+    pub inline_resource_paths: bool,
+    //tables and RSZ blocks start on multiples of this
+    pub table_alignment: usize,
+}
+
+pub const SF6: GameProfile = GameProfile {
+    game: Game::SF6,
+    schema: Some("rszsf6.json"),
+    fchar_version: Some(17),
+    pfb_version: 17,
+    scn_version: 20,
+    user_version: 2,
+    rsz_version: SF6_VERSION,
+    prefab_userdata: true,
+    inline_resource_paths: false,
+    table_alignment: 16,
+};
+
+pub const DMC5: GameProfile = GameProfile {
+    game: Game::DMC5,
+    schema: Some("rszdmc5.json"),
+    fchar_version: None,
+    pfb_version: 16,
+    scn_version: 19,
+    user_version: 2,
+    rsz_version: DMC5_VERSION,
+    prefab_userdata: false,
+    inline_resource_paths: true,
+    table_alignment: 16,
+};
+
+//Other titles get a Game variant and a profile here once their versions and layout have been checked against real files
+pub const PROFILES: [GameProfile; 2] = [SF6, DMC5];
+
+impl GameProfile {
+    pub fn file_version(&self, kind: FileKind) -> Option<u32> {
+        match kind {
+            FileKind::CharacterAsset => self.fchar_version,
+            FileKind::Prefab => Some(self.pfb_version),
+            FileKind::Scene => Some(self.scn_version),
+            FileKind::UserData => Some(self.user_version),
+            FileKind::Rsz => Some(self.rsz_version),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::fmt;
 use std::io::Write;
 use nom::bytes::complete::{take, take_until};
 use nom::combinator::map;
@@ -6,18 +7,24 @@ use nom::multi::count;
 use nom::number::complete::{le_f32, le_f64, le_i16, le_i32, le_i64, le_i8, le_u16, le_u32, le_u64, le_u8};
 use nom::sequence::tuple;
 use serde::{Deserialize, Serialize};
+use crate::detect::RSZ_MAGIC;
+use crate::profile::GameProfile;
+use crate::rsz::version::{rsz_layout, UserDataLayout};
 use crate::rsz::json_parser::{get_field_array_state, get_field_count, get_field_name, get_field_size, get_field_type, TypeIDs, get_field_alignment};
 
 pub mod json_parser;
+pub mod version;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct InstanceInfo {
     pub hash: u32,
     pub crc: u32,
 }
 
-fn parse_instance_info(input: &[u8]) -> IResult<&[u8], InstanceInfo> {
-    map(
+//Bytes past the hash and crc are skipped, so entries of any size in the layout can be read
+fn parse_instance_info(input: &[u8], size: usize) -> IResult<&[u8], InstanceInfo> {
+    let (remainder, entry) = take(size)(input)?;
+    let (_, instance_info) = map(
         tuple((
             le_u32,
             le_u32,
@@ -31,29 +38,30 @@ fn parse_instance_info(input: &[u8]) -> IResult<&[u8], InstanceInfo> {
                 crc,
             }
         }
-    )(input)
+    )(entry)?;
+    Ok((remainder, instance_info))
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct PlaneXZ {
     pub x: f32,
     pub z: f32,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct Float2 {
     pub x: f32,
     pub y: f32,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct Float3 {
     pub x: f32,
     pub y: f32,
     pub z: f32,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct Float4 {
     pub x: f32,
     pub y: f32,
@@ -61,20 +69,20 @@ pub struct Float4 {
     pub w: f32,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct Int2 {
     pub x: i32,
     pub y: i32,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct Int3 {
     pub x: i32,
     pub y: i32,
     pub z: i32,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct Int4 {
     pub x: i32,
     pub y: i32,
@@ -82,20 +90,20 @@ pub struct Int4 {
     pub w: i32,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct UInt2 {
     pub x: u32,
     pub y: u32,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct UInt3 {
     pub x: u32,
     pub y: u32,
     pub z: u32,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct UInt4 {
     pub x: u32,
     pub y: u32,
@@ -103,12 +111,12 @@ pub struct UInt4 {
     pub w: u32,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct GUID {
     uuid: [u8; 16],
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub enum RSZValue {
     Bool(bool),
     Float(f32),
@@ -117,7 +125,11 @@ pub enum RSZValue {
     Float2(Float2),
     Float3(Float3),
     Float4(Float4),
-    Fixed(f32),
+    //16.16 fixed point as stored in the file, the vectors keep each component the same way
+    Fixed(i32),
+    Fixed2(Int2),
+    Fixed3(Int3),
+    Fixed4(Int4),
     GUID(GUID),
     Int8(i8),
     Int16(i16),
@@ -138,7 +150,184 @@ pub enum RSZValue {
     List(Vec<RSZValue>)
 }
 
-#[derive(Serialize, Deserialize)]
+impl fmt::Display for RSZValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RSZValue::Bool(value) => write!(f, "{}", value),
+            RSZValue::Float(value) => write!(f, "{}", value),
+            RSZValue::Double(value) => write!(f, "{}", value),
+            RSZValue::PlaneXZ(value) => write!(f, "({}, {})", value.x, value.z),
+            RSZValue::Float2(value) => write!(f, "({}, {})", value.x, value.y),
+            RSZValue::Float3(value) => write!(f, "({}, {}, {})", value.x, value.y, value.z),
+            RSZValue::Float4(value) => write!(f, "({}, {}, {}, {})", value.x, value.y, value.z, value.w),
+            RSZValue::Fixed(value) => write!(f, "{}", fixed(*value)),
+            RSZValue::Fixed2(value) => write!(f, "({}, {})", fixed(value.x), fixed(value.y)),
+            RSZValue::Fixed3(value) => write!(f, "({}, {}, {})", fixed(value.x), fixed(value.y), fixed(value.z)),
+            RSZValue::Fixed4(value) => write!(f, "({}, {}, {}, {})", fixed(value.x), fixed(value.y), fixed(value.z), fixed(value.w)),
+            RSZValue::GUID(value) => {
+                for byte in value.uuid {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+            RSZValue::Int8(value) => write!(f, "{}", value),
+            RSZValue::Int16(value) => write!(f, "{}", value),
+            RSZValue::Int32(value) => write!(f, "{}", value),
+            RSZValue::Int64(value) => write!(f, "{}", value),
+            RSZValue::UInt8(value) => write!(f, "{}", value),
+            RSZValue::UInt16(value) => write!(f, "{}", value),
+            RSZValue::UInt32(value) => write!(f, "{}", value),
+            RSZValue::UInt64(value) => write!(f, "{}", value),
+            RSZValue::Int2(value) => write!(f, "({}, {})", value.x, value.y),
+            RSZValue::Int3(value) => write!(f, "({}, {}, {})", value.x, value.y, value.z),
+            RSZValue::Int4(value) => write!(f, "({}, {}, {}, {})", value.x, value.y, value.z, value.w),
+            RSZValue::UInt2(value) => write!(f, "({}, {})", value.x, value.y),
+            RSZValue::UInt3(value) => write!(f, "({}, {}, {})", value.x, value.y, value.z),
+            RSZValue::UInt4(value) => write!(f, "({}, {}, {}, {})", value.x, value.y, value.z, value.w),
+            RSZValue::String(value) => write!(f, "{}", value),
+            RSZValue::Unk(value) => {
+                for byte in value {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+            RSZValue::List(values) => {
+                write!(f, "[")?;
+                for (n, value) in values.iter().enumerate() {
+                    if n != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl RSZValue {
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            RSZValue::Bool(value) => Some(*value as i64),
+            RSZValue::Int8(value) => Some(*value as i64),
+            RSZValue::Int16(value) => Some(*value as i64),
+            RSZValue::Int32(value) => Some(*value as i64),
+            RSZValue::Int64(value) => Some(*value),
+            RSZValue::UInt8(value) => Some(*value as i64),
+            RSZValue::UInt16(value) => Some(*value as i64),
+            RSZValue::UInt32(value) => Some(*value as i64),
+            RSZValue::UInt64(value) => Some(*value as i64),
+            _ => None,
+        }
+    }
+
+    //Stores the value in the existing integer variant, returns false if the value isn't an integer
+    pub fn set_i64(&mut self, new_value: i64) -> bool {
+        match self {
+            RSZValue::Bool(value) => *value = new_value != 0,
+            RSZValue::Int8(value) => *value = new_value as i8,
+            RSZValue::Int16(value) => *value = new_value as i16,
+            RSZValue::Int32(value) => *value = new_value as i32,
+            RSZValue::Int64(value) => *value = new_value,
+            RSZValue::UInt8(value) => *value = new_value as u8,
+            RSZValue::UInt16(value) => *value = new_value as u16,
+            RSZValue::UInt32(value) => *value = new_value as u32,
+            RSZValue::UInt64(value) => *value = new_value as u64,
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            RSZValue::Float(value) => Some(*value as f64),
+            RSZValue::Double(value) => Some(*value),
+            RSZValue::Fixed(value) => Some(fixed(*value)),
+            _ => self.as_i64().map(|value| value as f64),
+        }
+    }
+
+    //Vector and scalar values as a list of components, e.g. x, y, z, w
+    pub fn components(&self) -> Option<Vec<f64>> {
+        match self {
+            RSZValue::PlaneXZ(value) => Some(vec![value.x as f64, value.z as f64]),
+            RSZValue::Float2(value) => Some(vec![value.x as f64, value.y as f64]),
+            RSZValue::Float3(value) => Some(vec![value.x as f64, value.y as f64, value.z as f64]),
+            RSZValue::Float4(value) => Some(vec![value.x as f64, value.y as f64, value.z as f64, value.w as f64]),
+            RSZValue::Fixed2(value) => Some(vec![fixed(value.x), fixed(value.y)]),
+            RSZValue::Fixed3(value) => Some(vec![fixed(value.x), fixed(value.y), fixed(value.z)]),
+            RSZValue::Fixed4(value) => Some(vec![fixed(value.x), fixed(value.y), fixed(value.z), fixed(value.w)]),
+            RSZValue::Int2(value) => Some(vec![value.x as f64, value.y as f64]),
+            RSZValue::Int3(value) => Some(vec![value.x as f64, value.y as f64, value.z as f64]),
+            RSZValue::Int4(value) => Some(vec![value.x as f64, value.y as f64, value.z as f64, value.w as f64]),
+            RSZValue::UInt2(value) => Some(vec![value.x as f64, value.y as f64]),
+            RSZValue::UInt3(value) => Some(vec![value.x as f64, value.y as f64, value.z as f64]),
+            RSZValue::UInt4(value) => Some(vec![value.x as f64, value.y as f64, value.z as f64, value.w as f64]),
+            _ => self.as_f64().map(|value| vec![value]),
+        }
+    }
+
+    //Returns false if the value isn't a vector of the same length
+    pub fn set_components(&mut self, components: &[f64]) -> bool {
+        match (self, components) {
+            (RSZValue::PlaneXZ(value), [x, z]) => {
+                (value.x, value.z) = (*x as f32, *z as f32);
+            }
+            (RSZValue::Float2(value), [x, y]) => {
+                (value.x, value.y) = (*x as f32, *y as f32);
+            }
+            (RSZValue::Float3(value), [x, y, z]) => {
+                (value.x, value.y, value.z) = (*x as f32, *y as f32, *z as f32);
+            }
+            (RSZValue::Float4(value), [x, y, z, w]) => {
+                (value.x, value.y, value.z, value.w) = (*x as f32, *y as f32, *z as f32, *w as f32);
+            }
+            (RSZValue::Fixed2(value), [x, y]) => {
+                (value.x, value.y) = (to_fixed(*x), to_fixed(*y));
+            }
+            (RSZValue::Fixed3(value), [x, y, z]) => {
+                (value.x, value.y, value.z) = (to_fixed(*x), to_fixed(*y), to_fixed(*z));
+            }
+            (RSZValue::Fixed4(value), [x, y, z, w]) => {
+                (value.x, value.y, value.z, value.w) = (to_fixed(*x), to_fixed(*y), to_fixed(*z), to_fixed(*w));
+            }
+            (RSZValue::Int2(value), [x, y]) => {
+                (value.x, value.y) = (x.round() as i32, y.round() as i32);
+            }
+            (RSZValue::Int3(value), [x, y, z]) => {
+                (value.x, value.y, value.z) = (x.round() as i32, y.round() as i32, z.round() as i32);
+            }
+            (RSZValue::Int4(value), [x, y, z, w]) => {
+                (value.x, value.y, value.z, value.w) = (x.round() as i32, y.round() as i32, z.round() as i32, w.round() as i32);
+            }
+            (RSZValue::UInt2(value), [x, y]) => {
+                (value.x, value.y) = (x.round() as u32, y.round() as u32);
+            }
+            (RSZValue::UInt3(value), [x, y, z]) => {
+                (value.x, value.y, value.z) = (x.round() as u32, y.round() as u32, z.round() as u32);
+            }
+            (RSZValue::UInt4(value), [x, y, z, w]) => {
+                (value.x, value.y, value.z, value.w) = (x.round() as u32, y.round() as u32, z.round() as u32, w.round() as u32);
+            }
+            (value, [x]) => return value.set_f64(*x),
+            _ => return false,
+        }
+        true
+    }
+
+    //Integer variants are rounded to the nearest value
+    pub fn set_f64(&mut self, new_value: f64) -> bool {
+        match self {
+            RSZValue::Float(value) => *value = new_value as f32,
+            RSZValue::Double(value) => *value = new_value,
+            RSZValue::Fixed(value) => *value = to_fixed(new_value),
+            _ => return self.set_i64(new_value.round() as i64),
+        }
+        true
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct RSZField {
     pub name: String,
     pub value_type: TypeIDs,
@@ -146,123 +335,194 @@ pub struct RSZField {
     pub alignment: usize,
 }
 
-#[derive(Serialize, Deserialize)]
+impl RSZField {
+    //Instances an object field points at, 0 being null
+    pub fn child_instances(&self) -> Vec<i32> {
+        if self.value_type != TypeIDs::Object {
+            return vec![];
+        }
+        let ids = match &self.value {
+            RSZValue::List(values) => values.iter().filter_map(|value| value.as_i64()).collect(),
+            value => value.as_i64().into_iter().collect::<Vec<i64>>(),
+        };
+        ids.into_iter().map(|id| id as i32).filter(|id| *id > 0).collect()
+    }
+
+    //Rewrites the instance ids an object field points at, null references are left alone
+    pub fn map_child_instances(&mut self, mut map: impl FnMut(i32) -> i32) {
+        if self.value_type != TypeIDs::Object {
+            return;
+        }
+        let values: Vec<&mut RSZValue> = match &mut self.value {
+            RSZValue::List(values) => values.iter_mut().collect(),
+            value => vec![value],
+        };
+        for value in values {
+            if let RSZValue::Int32(id) = value {
+                if *id > 0 {
+                    *id = map(*id);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct RSZData {
     pub name: String,
     pub fields: Vec<RSZField>,
 }
 
+pub(crate) fn normalize_field_name(name: &str) -> String {
+    name.chars().filter(|c| *c != '_').collect::<String>().to_lowercase()
+}
+
+impl RSZData {
+    //Field names are compared case-insensitively and without underscores, so "action_id" finds "ActionID"
+    pub fn find_field(&self, name: &str) -> Option<&RSZField> {
+        let name = normalize_field_name(name);
+        self.fields.iter().find(|field| normalize_field_name(&field.name) == name)
+    }
+
+    pub fn find_field_mut(&mut self, name: &str) -> Option<&mut RSZField> {
+        let name = normalize_field_name(name);
+        self.fields.iter_mut().find(|field| normalize_field_name(&field.name) == name)
+    }
+
+    pub fn get_i64(&self, name: &str) -> Option<i64> {
+        self.find_field(name)?.value.as_i64()
+    }
+
+    pub fn get_f64(&self, name: &str) -> Option<f64> {
+        self.find_field(name)?.value.as_f64()
+    }
+
+    pub fn set_i64(&mut self, name: &str, value: i64) -> bool {
+        match self.find_field_mut(name) {
+            Some(field) => field.value.set_i64(value),
+            None => false,
+        }
+    }
+
+    pub fn set_f64(&mut self, name: &str, value: f64) -> bool {
+        match self.find_field_mut(name) {
+            Some(field) => field.value.set_f64(value),
+            None => false,
+        }
+    }
+}
+
 fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usize, alignment: usize) -> IResult<&[u8], RSZValue>
 {
     let field_size = get_field_size(&hash, &n);
-    let mut remainder: &[u8] = &input[offset.clone()..];
+    let mut remainder: &[u8] = input.get(offset..).ok_or_else(|| verify_error(input))?;
     let alignment_remainder = (16 - (input.len() - remainder.len()) % 16) % alignment;
     if alignment_remainder != 0 {
-        remainder = &remainder[alignment_remainder..];
+        remainder = remainder.get(alignment_remainder..).ok_or_else(|| verify_error(input))?;
     }
     let base_remainder = remainder;
     let value = match field_type
     {
         TypeIDs::Object => {
             let mut int = 0i32;
-            (remainder, int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Int32(int.clone())
         }
         TypeIDs::Resource => {
             let mut uint = 0u32;
-            (remainder, uint) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-            let (_, mut string) = take_str_of_size(remainder, uint * 2).unwrap_or((remainder, "".to_string()));
+            (remainder, uint) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+            let (_, mut string) = take_str_of_size(remainder, uint.saturating_mul(2)).unwrap_or((remainder, "".to_string()));
             string = string.replace("\u{0}", "");
-            remainder = &remainder[uint as usize * 2..];
+            remainder = remainder.get(uint as usize * 2..).ok_or_else(|| verify_error(input))?;
             RSZValue::String(string)
         }
         TypeIDs::UserData => {
             let mut int = 0i32;
-            (remainder, int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Int32(int.clone())
         }
         TypeIDs::Bool => {
             let mut bool = 0u8;
-            (remainder, bool) = le_u8::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, bool) = le_u8::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Bool(bool > 0)
         }
         TypeIDs::S8 => {
             let mut byte = 0i8;
-            (remainder, byte) = le_i8::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, byte) = le_i8::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Int8(byte.clone())
         }
         TypeIDs::U8 => {
             let mut ubyte = 0u8;
-            (remainder, ubyte) = le_u8::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, ubyte) = le_u8::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::UInt8(ubyte.clone())
         }
         TypeIDs::S16 => {
             let mut short = 0i16;
-            (remainder, short) = le_i16::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, short) = le_i16::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Int16(short.clone())
         }
         TypeIDs::U16 => {
             let mut ushort = 0u16;
-            (remainder, ushort) = le_u16::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, ushort) = le_u16::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::UInt16(ushort.clone())
         }
         TypeIDs::S32 => {
             let mut int = 0i32;
-            (remainder, int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Int32(int.clone())
         }
         TypeIDs::U32 => {
             let mut uint = 0u32;
-            (remainder, uint) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, uint) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::UInt32(uint.clone())
         }
         TypeIDs::S64 => {
             let mut long = 0i64;
-            (remainder, long) = le_i64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, long) = le_i64::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Int64(long.clone())
         }
         TypeIDs::U64 => {
             let mut ulong = 0u64;
-            (remainder, ulong) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, ulong) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::UInt64(ulong.clone())
         }
         TypeIDs::F32 => {
             let mut float = 0f32;
-            (remainder, float) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, float) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Float(float.clone())
         }
         TypeIDs::F64 => {
             let mut double = 0f64;
-            (remainder, double) = le_f64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, double) = le_f64::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Double(double.clone())
         }
         TypeIDs::String => {
             let mut uint = 0u32;
-            (remainder, uint) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-            let (_, mut string) = take_str_of_size(remainder, uint * 2).unwrap_or((remainder, "".to_string()));
+            (remainder, uint) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+            let (_, mut string) = take_str_of_size(remainder, uint.saturating_mul(2)).unwrap_or((remainder, "".to_string()));
             string = string.replace("\u{0}", "");
-            remainder = &remainder[uint as usize * 2..];
+            remainder = remainder.get(uint as usize * 2..).ok_or_else(|| verify_error(input))?;
             RSZValue::String(string)
         }
         TypeIDs::MBString => {
             /*let alignment_remainder = (16 -(input.len() - remainder.len()) % 16) % 4;
             if alignment_remainder != 0 {
-                remainder = &remainder[alignment_remainder..];
+                remainder = remainder.get(alignment_remainder..).ok_or_else(|| verify_error(input))?;
             }*/
             let mut data: &[u8] = &[];
-            (remainder, data) = take::<usize, &[u8], nom::error::Error<&[u8]>>(field_size)(remainder).unwrap();
+            (remainder, data) = take::<usize, &[u8], nom::error::Error<&[u8]>>(field_size)(remainder)?;
             RSZValue::Unk(data.to_vec())
         }
         TypeIDs::Enum => {
             let mut int = 0i32;
-            (remainder, int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Int32(int.clone())
         }
         TypeIDs::Uint2 => {
             let mut x = 0u32;
-            (remainder, x) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut y = 0u32;
-            (remainder, y) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, y) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::UInt2(UInt2{
                 x: x.clone(),
                 y: y.clone(),
@@ -270,11 +530,11 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::Uint3 => {
             let mut x = 0u32;
-            (remainder, x) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut y = 0u32;
-            (remainder, y) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, y) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut z = 0u32;
-            (remainder, z) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, z) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::UInt3(UInt3{
                 x: x.clone(),
                 y: y.clone(),
@@ -283,13 +543,13 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::Uint4 => {
             let mut x = 0u32;
-            (remainder, x) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut y = 0u32;
-            (remainder, y) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, y) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut z = 0u32;
-            (remainder, z) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, z) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut w = 0u32;
-            (remainder, w) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, w) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::UInt4(UInt4{
                 x: x.clone(),
                 y: y.clone(),
@@ -299,9 +559,9 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::Int2 => {
             let mut x = 0i32;
-            (remainder, x) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut y = 0i32;
-            (remainder, y) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, y) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Int2(Int2{
                 x: x.clone(),
                 y: y.clone(),
@@ -309,11 +569,11 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::Int3 => {
             let mut x = 0i32;
-            (remainder, x) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut y = 0i32;
-            (remainder, y) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, y) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut z = 0i32;
-            (remainder, z) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, z) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Int3(Int3{
                 x: x.clone(),
                 y: y.clone(),
@@ -322,13 +582,13 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::Int4 => {
             let mut x = 0i32;
-            (remainder, x) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut y = 0i32;
-            (remainder, y) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, y) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut z = 0i32;
-            (remainder, z) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, z) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut w = 0i32;
-            (remainder, w) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, w) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Int4(Int4{
                 x: x.clone(),
                 y: y.clone(),
@@ -338,9 +598,9 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::Float2 => {0f32;
             let mut x = 0f32;
-            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut y = 0f32;
-            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Float2(Float2{
                 x: x.clone(),
                 y: y.clone(),
@@ -348,11 +608,11 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::Float3 => {
             let mut x = 0f32;
-            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut y = 0f32;
-            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut z = 0f32;
-            (remainder, z) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, z) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Float3(Float3{
                 x: x.clone(),
                 y: y.clone(),
@@ -361,13 +621,13 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::Float4 => {
             let mut x = 0f32;
-            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut y = 0f32;
-            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut z = 0f32;
-            (remainder, z) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, z) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut w = 0f32;
-            (remainder, w) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, w) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Float4(Float4{
                 x: x.clone(),
                 y: y.clone(),
@@ -377,9 +637,9 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::Vec2 => {
             let mut x = 0f32;
-            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut y = 0f32;
-            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Float2(Float2{
                 x: x.clone(),
                 y: y.clone(),
@@ -387,11 +647,11 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::Vec3 => {
             let mut x = 0f32;
-            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut y = 0f32;
-            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut z = 0f32;
-            (remainder, z) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, z) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Float3(Float3{
                 x: x.clone(),
                 y: y.clone(),
@@ -400,13 +660,13 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::Vec4 => {
             let mut x = 0f32;
-            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut y = 0f32;
-            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut z = 0f32;
-            (remainder, z) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, z) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut w = 0f32;
-            (remainder, w) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, w) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Float4(Float4{
                 x: x.clone(),
                 y: y.clone(),
@@ -416,13 +676,13 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::Quaternion => {
             let mut x = 0f32;
-            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut y = 0f32;
-            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut z = 0f32;
-            (remainder, z) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, z) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut w = 0f32;
-            (remainder, w) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, w) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Float4(Float4{
                 x: x.clone(),
                 y: y.clone(),
@@ -432,26 +692,26 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::Guid => {
             let mut data: &[u8] = &[];
-            (remainder, data) = take::<usize, &[u8], nom::error::Error<&[u8]>>(field_size)(remainder).unwrap();
+            (remainder, data) = take::<usize, &[u8], nom::error::Error<&[u8]>>(field_size)(remainder)?;
             RSZValue::GUID(GUID {
                 uuid: data.try_into().unwrap()
             })
         }
         TypeIDs::Color => {
             let mut uint = 0u32;
-            (remainder, uint) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, uint) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::UInt32(uint.clone())
         }
         TypeIDs::DateTime => {
             let mut long = 0i64;
-            (remainder, long) = le_i64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, long) = le_i64::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Int64(long.clone())
         }
         TypeIDs::PlaneXZ => {
             let mut x = 0f32;
-            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut z = 0f32;
-            (remainder, z) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, z) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::PlaneXZ(PlaneXZ{
                 x: x.clone(),
                 z: z.clone(),
@@ -459,9 +719,9 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::Point => {
             let mut x = 0f32;
-            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut y = 0f32;
-            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Float2(Float2{
                 x: x.clone(),
                 y: y.clone(),
@@ -469,9 +729,9 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::Range => {
             let mut x = 0f32;
-            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut y = 0f32;
-            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, y) = le_f32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::Float2(Float2{
                 x: x.clone(),
                 y: y.clone(),
@@ -479,9 +739,9 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::RangeI => {
             let mut x = 0u32;
-            (remainder, x) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, x) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             let mut y = 0u32;
-            (remainder, y) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+            (remainder, y) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
             RSZValue::UInt2(UInt2{
                 x: x.clone(),
                 y: y.clone(),
@@ -489,66 +749,56 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         TypeIDs::Uri => {
             let mut data: &[u8] = &[];
-            (remainder, data) = take::<usize, &[u8], nom::error::Error<&[u8]>>(field_size)(remainder).unwrap();
+            (remainder, data) = take::<usize, &[u8], nom::error::Error<&[u8]>>(field_size)(remainder)?;
             RSZValue::GUID(GUID {
                 uuid: data.try_into().unwrap()
             })
         }
         TypeIDs::GameObjectRef => {
             let mut data: &[u8] = &[];
-            (remainder, data) = take::<usize, &[u8], nom::error::Error<&[u8]>>(field_size)(remainder).unwrap();
+            (remainder, data) = take::<usize, &[u8], nom::error::Error<&[u8]>>(field_size)(remainder)?;
             RSZValue::GUID(GUID {
                 uuid: data.try_into().unwrap()
             })
         }
         TypeIDs::Sfix => {
             let mut fix = 0i32;
-            (remainder, fix) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-            let float = fix.clone() as f32 / 65536.0f32;
-            RSZValue::Float(float)
+            (remainder, fix) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+            RSZValue::Fixed(fix)
         }
         TypeIDs::Sfix2 => {
-            let mut x_int = 0i32;
-            (remainder, x_int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-            let x = x_int.clone() as f32 / 65536.0f32;
-            let mut y_int = 0i32;
-            (remainder, y_int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-            let y = y_int.clone() as f32 / 65536.0f32;
-            RSZValue::Float2(Float2{
+            let x: i32;
+            (remainder, x) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+            let y: i32;
+            (remainder, y) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+            RSZValue::Fixed2(Int2{
                 x,
                 y,
             })
         }
         TypeIDs::Sfix3 => {
-            let mut x_int = 0i32;
-            (remainder, x_int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-            let x = x_int.clone() as f32 / 65536.0f32;
-            let mut y_int = 0i32;
-            (remainder, y_int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-            let y = y_int.clone() as f32 / 65536.0f32;
-            let mut z_int = 0i32;
-            (remainder, z_int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-            let z = z_int.clone() as f32 / 65536.0f32;
-            RSZValue::Float3(Float3{
+            let x: i32;
+            (remainder, x) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+            let y: i32;
+            (remainder, y) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+            let z: i32;
+            (remainder, z) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+            RSZValue::Fixed3(Int3{
                 x,
                 y,
                 z,
             })
         }
         TypeIDs::Sfix4 => {
-            let mut x_int = 0i32;
-            (remainder, x_int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-            let x = x_int.clone() as f32 / 65536.0f32;
-            let mut y_int = 0i32;
-            (remainder, y_int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-            let y = y_int.clone() as f32 / 65536.0f32;
-            let mut z_int = 0i32;
-            (remainder, z_int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-            let z = z_int.clone() as f32 / 65536.0f32;
-            let mut w_int = 0i32;
-            (remainder, w_int) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-            let w = w_int.clone() as f32 / 65536.0f32;
-            RSZValue::Float4(Float4{
+            let x: i32;
+            (remainder, x) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+            let y: i32;
+            (remainder, y) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+            let z: i32;
+            (remainder, z) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+            let w: i32;
+            (remainder, w) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+            RSZValue::Fixed4(Int4{
                 x,
                 y,
                 z,
@@ -557,12 +807,12 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
         }
         _ => {
             let mut data: &[u8] = &[];
-            (remainder, data) = take::<usize, &[u8], nom::error::Error<&[u8]>>(field_size)(remainder).unwrap();
+            (remainder, data) = take::<usize, &[u8], nom::error::Error<&[u8]>>(field_size)(remainder)?;
             RSZValue::Unk(data.to_vec())
         }
     };
     if field_type != TypeIDs::String && field_type != TypeIDs::Resource {
-        Ok((&base_remainder[field_size..], value))
+        Ok((base_remainder.get(field_size..).ok_or_else(|| verify_error(input))?, value))
     }
     else {
         Ok((remainder, value))
@@ -570,9 +820,10 @@ fn get_value(input: &[u8], offset: usize, field_type: TypeIDs, hash: u32, n: usi
 }
 
 fn parse_rsz_data(input: &[u8], offset: usize, hash: u32) -> IResult<&[u8], RSZData> {
-    let name = json_parser::get_rsz_class_name(&hash).unwrap();
+    //classes missing from the schema usually mean a dump from another patch or game
+    let name = json_parser::get_rsz_class_name(&hash).map_err(|_| verify_error(input))?;
     let mut fields: Vec<RSZField> = vec![];
-    let mut remainder: &[u8] = &input[offset.clone()..];
+    let mut remainder: &[u8] = input.get(offset..).ok_or_else(|| verify_error(input))?;
     for n in 0..get_field_count(&hash)
     {
         let field_type = get_field_type(&hash, &n);
@@ -583,14 +834,14 @@ fn parse_rsz_data(input: &[u8], offset: usize, hash: u32) -> IResult<&[u8], RSZD
             let mut new_remainder = remainder;
             let alignment_remainder = (16 -(input.len() - new_remainder.len()) % 16) % 4;
             if alignment_remainder != 0 {
-                new_remainder = &new_remainder[alignment_remainder..];
+                new_remainder = new_remainder.get(alignment_remainder..).ok_or_else(|| verify_error(input))?;
             }
             let mut count: u32 = 0;
-            (new_remainder, count) = le_u32::<&[u8], nom::error::Error<&[u8]>>(new_remainder).unwrap();
+            (new_remainder, count) = le_u32::<&[u8], nom::error::Error<&[u8]>>(new_remainder)?;
             let mut values: Vec<RSZValue> = vec![];
             for _ in 0..count {
                 let offset = input.len() - new_remainder.len();
-                let (value_remainder, value) = get_value(input, offset, field_type, hash.clone(), n.clone(), field_alignment.clone()).unwrap();
+                let (value_remainder, value) = get_value(input, offset, field_type, hash, n, field_alignment)?;
                 values.push(value);
                 new_remainder = value_remainder;
             }
@@ -605,7 +856,7 @@ fn parse_rsz_data(input: &[u8], offset: usize, hash: u32) -> IResult<&[u8], RSZD
         }
         else {
             let offset = input.len() - remainder.len();
-            let (new_remainder, value) = get_value(input, offset, field_type, hash.clone(), n, field_alignment).unwrap();
+            let (new_remainder, value) = get_value(input, offset, field_type, hash, n, field_alignment)?;
             fields.push(
                 RSZField{
                     name: get_field_name(&hash, &n),
@@ -626,108 +877,121 @@ fn parse_rsz_data(input: &[u8], offset: usize, hash: u32) -> IResult<&[u8], RSZD
 fn write_rsz_value(value: &RSZValue, bytes: &mut Vec<u8>) {
     match value {
         RSZValue::Bool(value) => {
-            bytes.write(&[value.clone() as u8]).unwrap();
+            bytes.write_all(&[*value as u8]).unwrap();
         }
         RSZValue::Float(value) => {
-            bytes.write(&value.to_le_bytes()[0..3]).unwrap();
+            bytes.write_all(&value.to_le_bytes()).unwrap();
         }
         RSZValue::Double(value) => {
-            bytes.write(&value.to_le_bytes()[0..7]).unwrap();
+            bytes.write_all(&value.to_le_bytes()).unwrap();
         }
         RSZValue::PlaneXZ(value) => {
-            bytes.write(&value.x.to_le_bytes()[0..3]).unwrap();
-            bytes.write(&value.z.to_le_bytes()[0..3]).unwrap();
+            bytes.write_all(&value.x.to_le_bytes()).unwrap();
+            bytes.write_all(&value.z.to_le_bytes()).unwrap();
         }
         RSZValue::Float2(value) => {
-            bytes.write(&value.x.to_le_bytes()[0..3]).unwrap();
-            bytes.write(&value.y.to_le_bytes()[0..3]).unwrap();
+            bytes.write_all(&value.x.to_le_bytes()).unwrap();
+            bytes.write_all(&value.y.to_le_bytes()).unwrap();
         }
         RSZValue::Float3(value) => {
-            bytes.write(&value.x.to_le_bytes()[0..3]).unwrap();
-            bytes.write(&value.y.to_le_bytes()[0..3]).unwrap();
-            bytes.write(&value.z.to_le_bytes()[0..3]).unwrap();
+            bytes.write_all(&value.x.to_le_bytes()).unwrap();
+            bytes.write_all(&value.y.to_le_bytes()).unwrap();
+            bytes.write_all(&value.z.to_le_bytes()).unwrap();
         }
         RSZValue::Float4(value) => {
-            bytes.write(&value.x.to_le_bytes()[0..3]).unwrap();
-            bytes.write(&value.y.to_le_bytes()[0..3]).unwrap();
-            bytes.write(&value.z.to_le_bytes()[0..3]).unwrap();
-            bytes.write(&value.w.to_le_bytes()[0..3]).unwrap();
+            bytes.write_all(&value.x.to_le_bytes()).unwrap();
+            bytes.write_all(&value.y.to_le_bytes()).unwrap();
+            bytes.write_all(&value.z.to_le_bytes()).unwrap();
+            bytes.write_all(&value.w.to_le_bytes()).unwrap();
         }
         RSZValue::Fixed(value) => {
-            let fix1: u32 = (value * 65536.0) as u32;
-            bytes.write(&fix1.to_le_bytes()[0..3]).unwrap();
+            bytes.write_all(&value.to_le_bytes()).unwrap();
+        }
+        RSZValue::Fixed2(value) => {
+            bytes.write_all(&value.x.to_le_bytes()).unwrap();
+            bytes.write_all(&value.y.to_le_bytes()).unwrap();
+        }
+        RSZValue::Fixed3(value) => {
+            bytes.write_all(&value.x.to_le_bytes()).unwrap();
+            bytes.write_all(&value.y.to_le_bytes()).unwrap();
+            bytes.write_all(&value.z.to_le_bytes()).unwrap();
+        }
+        RSZValue::Fixed4(value) => {
+            bytes.write_all(&value.x.to_le_bytes()).unwrap();
+            bytes.write_all(&value.y.to_le_bytes()).unwrap();
+            bytes.write_all(&value.z.to_le_bytes()).unwrap();
+            bytes.write_all(&value.w.to_le_bytes()).unwrap();
         }
         RSZValue::GUID(value) => {
-            bytes.write(&value.uuid[0..15]).unwrap();
+            bytes.write_all(&value.uuid[..]).unwrap();
         }
         RSZValue::Int8(value) => {
-            bytes.write(&(value.clone() as u8).to_le_bytes()[0..1]).unwrap();
+            bytes.write_all(&(*value as u8).to_le_bytes()[0..1]).unwrap();
         }
         RSZValue::Int16(value) => {
-            bytes.write(&(value.clone() as u16).to_le_bytes()[0..2]).unwrap();
+            bytes.write_all(&(*value as u16).to_le_bytes()[0..2]).unwrap();
         }
         RSZValue::Int32(value) => {
-            bytes.write(&(value.clone() as u32).to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&(*value as u32).to_le_bytes()[0..4]).unwrap();
         }
         RSZValue::Int64(value) => {
-            bytes.write(&(value.clone() as u64).to_le_bytes()[0..8]).unwrap();
+            bytes.write_all(&(*value as u64).to_le_bytes()[0..8]).unwrap();
         }
         RSZValue::UInt8(value) => {
-            bytes.write(&value.to_le_bytes()[0..1]).unwrap();
+            bytes.write_all(&value.to_le_bytes()[0..1]).unwrap();
         }
         RSZValue::UInt16(value) => {
-            bytes.write(&value.to_le_bytes()[0..2]).unwrap();
+            bytes.write_all(&value.to_le_bytes()[0..2]).unwrap();
         }
         RSZValue::UInt32(value) => {
-            bytes.write(&value.to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&value.to_le_bytes()[0..4]).unwrap();
         }
         RSZValue::UInt64(value) => {
-            bytes.write(&value.to_le_bytes()[0..8]).unwrap();
+            bytes.write_all(&value.to_le_bytes()[0..8]).unwrap();
         }
         RSZValue::Int2(value) => {
-            bytes.write(&(value.x as u32).to_le_bytes()[0..4]).unwrap();
-            bytes.write(&(value.y as u32).to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&(value.x as u32).to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&(value.y as u32).to_le_bytes()[0..4]).unwrap();
         }
         RSZValue::Int3(value) => {
-            bytes.write(&(value.x as u32).to_le_bytes()[0..4]).unwrap();
-            bytes.write(&(value.y as u32).to_le_bytes()[0..4]).unwrap();
-            bytes.write(&(value.z as u32).to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&(value.x as u32).to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&(value.y as u32).to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&(value.z as u32).to_le_bytes()[0..4]).unwrap();
         }
         RSZValue::Int4(value) => {
-            bytes.write(&(value.x as u32).to_le_bytes()[0..4]).unwrap();
-            bytes.write(&(value.y as u32).to_le_bytes()[0..4]).unwrap();
-            bytes.write(&(value.z as u32).to_le_bytes()[0..4]).unwrap();
-            bytes.write(&(value.w as u32).to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&(value.x as u32).to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&(value.y as u32).to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&(value.z as u32).to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&(value.w as u32).to_le_bytes()[0..4]).unwrap();
         }
         RSZValue::UInt2(value) => {
-            bytes.write(&value.x.to_le_bytes()[0..4]).unwrap();
-            bytes.write(&value.y.to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&value.x.to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&value.y.to_le_bytes()[0..4]).unwrap();
         }
         RSZValue::UInt3(value) => {
-            bytes.write(&value.x.to_le_bytes()[0..4]).unwrap();
-            bytes.write(&value.y.to_le_bytes()[0..4]).unwrap();
-            bytes.write(&value.z.to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&value.x.to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&value.y.to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&value.z.to_le_bytes()[0..4]).unwrap();
         }
         RSZValue::UInt4(value) => {
-            bytes.write(&value.x.to_le_bytes()[0..4]).unwrap();
-            bytes.write(&value.y.to_le_bytes()[0..4]).unwrap();
-            bytes.write(&value.z.to_le_bytes()[0..4]).unwrap();
-            bytes.write(&value.w.to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&value.x.to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&value.y.to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&value.z.to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&value.w.to_le_bytes()[0..4]).unwrap();
         }
         RSZValue::String(value) => {
-            let strlen = value.len() + 1;
-            bytes.write(&(strlen as u32).to_le_bytes()[0..4]).unwrap();
-            for char in value.chars() {
-                bytes.write(&[char as u8]).unwrap();
-                bytes.write(&[0]).unwrap();
+            let chars: Vec<u16> = value.encode_utf16().collect();
+            bytes.write_all(&(chars.len() as u32 + 1).to_le_bytes()[0..4]).unwrap();
+            for char in chars {
+                bytes.write_all(&char.to_le_bytes()).unwrap();
             }
-            bytes.write(&[0; 2]).unwrap();
+            bytes.write_all(&[0; 2]).unwrap();
         }
         RSZValue::Unk(value) => {
-            bytes.write(&value[..]).unwrap();
+            bytes.write_all(&value[..]).unwrap();
         }
         RSZValue::List(values) => {
-            bytes.write(&(values.len() as u32).to_le_bytes()[0..4]).unwrap();
+            bytes.write_all(&(values.len() as u32).to_le_bytes()[0..4]).unwrap();
             for value in values {
                 write_rsz_value(value, bytes);
             }
@@ -735,47 +999,64 @@ fn write_rsz_value(value: &RSZValue, bytes: &mut Vec<u8>) {
     }
 }
 
-fn write_rsz_data(data: &RSZData, bytes: &mut Vec<u8>) {
-    for field in &data.fields {
-        if let RSZValue::List(list) = &field.value {
-            bytes.write(&(list.len() as u32).to_le_bytes()[0..4]).unwrap();
-            for value in list {
-                let alignment = (16 - bytes.len() % 16) % field.alignment;
-                if alignment != 0 {
-                    let mut null_bytes: Vec<u8> = vec![0; alignment];
-                    bytes.append(&mut null_bytes);
+//16.16 fixed point to and from the values shown and edited
+fn fixed(value: i32) -> f64 {
+    value as f64 / 65536.0
+}
+
+fn to_fixed(value: f64) -> i32 {
+    (value * 65536.0).round() as i32
+}
+
+fn write_field_value(value: &RSZValue, value_type: TypeIDs, field_size: usize, alignment: usize, bytes: &mut Vec<u8>) {
+    let alignment = (16 - bytes.len() % 16) % alignment;
+    bytes.resize(bytes.len() + alignment, 0);
+    let start = bytes.len();
+    write_rsz_value(value, bytes);
+    //values read as a smaller type than the field keep the field's size
+    if value_type != TypeIDs::String && value_type != TypeIDs::Resource && bytes.len() - start < field_size {
+        bytes.resize(start + field_size, 0);
+    }
+}
+
+//Alignment is relative to the start of the file, so bytes has to hold everything written before the data
+fn write_rsz_data(data: &RSZData, hash: u32, bytes: &mut Vec<u8>) {
+    for (n, field) in data.fields.iter().enumerate() {
+        let field_size = get_field_size(&hash, &n);
+        match &field.value {
+            RSZValue::List(list) => {
+                let alignment = (16 - bytes.len() % 16) % 4;
+                bytes.resize(bytes.len() + alignment, 0);
+                bytes.write_all(&(list.len() as u32).to_le_bytes()[0..4]).unwrap();
+                for value in list {
+                    write_field_value(value, field.value_type, field_size, field.alignment, bytes);
                 }
-                write_rsz_value(&value, bytes);
             }
-        }
-        else {
-            let alignment = (16 - bytes.len() % 16) % field.alignment;
-            if alignment != 0 {
-                let mut null_bytes: Vec<u8> = vec![0; alignment];
-                bytes.append(&mut null_bytes);
-            }
-            write_rsz_value(&field.value, bytes);
+            value => write_field_value(value, field.value_type, field_size, field.alignment, bytes),
         }
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct UserDataInfo {
     pub instance_id: u32,
     pub type_id: u32,
     #[serde(skip)]
     pub str_offset: u64,
     pub string: String,
+    //only set for userdata embedded in the RSZ instead of pointing at a .user file
+    pub json_path_hash: Option<u32>,
+    pub rsz: Option<RSZ>,
 }
 
 pub fn parse_userdata_info(input: &[u8], offset: usize) -> IResult<&[u8], UserDataInfo> {
-    let remainder = &input[offset..];
-    let (remainder, instance_id) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-    let (remainder, type_id) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
-    let (remainder, str_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+    let remainder = input.get(offset..).ok_or_else(|| verify_error(input))?;
+    let (remainder, instance_id) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+    let (remainder, type_id) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+    let (remainder, str_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
     
-    let str_remainder = &input[str_offset as usize..];
-    let (_, mut string) = map(take_until::<&str, &[u8], nom::error::Error<&[u8]>>("\0\0"), lossy_to_str)(str_remainder).unwrap();
+    let str_remainder = input.get(str_offset as usize..).ok_or_else(|| verify_error(input))?;
+    let (_, mut string) = map(take_until::<&str, &[u8], nom::error::Error<&[u8]>>("\0\0"), lossy_to_str)(str_remainder)?;
     string = string.replace("\u{0}", "");
 
     Ok((remainder, UserDataInfo {
@@ -783,6 +1064,32 @@ pub fn parse_userdata_info(input: &[u8], offset: usize) -> IResult<&[u8], UserDa
         type_id,
         str_offset,
         string,
+        json_path_hash: None,
+        rsz: None,
+    }))
+}
+
+//Embedded userdata carries its own RSZ block, stored after the userdata table and aligned on its own
+pub fn parse_embedded_userdata_info(input: &[u8], offset: usize) -> IResult<&[u8], UserDataInfo> {
+    let remainder = input.get(offset..).ok_or_else(|| verify_error(input))?;
+    let (remainder, instance_id) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+    let (remainder, type_id) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+    let (remainder, json_path_hash) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+    let (remainder, data_size) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+    let (remainder, rsz_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
+
+    let rsz_input = (rsz_offset as usize).checked_add(data_size as usize)
+        .and_then(|rsz_end| input.get(rsz_offset as usize..rsz_end))
+        .ok_or_else(|| verify_error(input))?;
+    let (_, rsz) = parse_rsz(rsz_input, 0)?;
+
+    Ok((remainder, UserDataInfo {
+        instance_id,
+        type_id,
+        str_offset: rsz_offset,
+        string: String::new(),
+        json_path_hash: Some(json_path_hash),
+        rsz: Some(rsz),
     }))
 }
 
@@ -793,7 +1100,7 @@ fn take_str_of_size(i: &[u8], size: u32) -> IResult<&[u8], String> {
     Ok((i, parsed_string))
 }
 
-fn lossy_to_str(i: &[u8]) -> String {
+pub fn lossy_to_str(i: &[u8]) -> String {
     String::from_utf8_lossy(i).to_string()
 }
 
@@ -825,6 +1132,82 @@ pub fn parse_gobject_info(input: &[u8]) -> IResult<&[u8], GameObjectInfo> {
     )(input)
 }
 
+//GameObject info of scene files, which also store the GameObject's GUID and the prefab it was spawned from
+#[derive(Serialize, Deserialize)]
+pub struct StandardGameObjectInfo {
+    pub guid: GUID,
+    pub id: i32,
+    pub parent_id: i32,
+    pub component_count: u16,
+    pub unknown: i16,
+    pub prefab_id: i32,
+}
+
+pub fn parse_standard_gobject_info(input: &[u8]) -> IResult<&[u8], StandardGameObjectInfo> {
+    map(
+        tuple((
+            take(16usize),
+            le_i32,
+            le_i32,
+            le_u16,
+            le_i16,
+            le_i32,
+        )),
+        |(
+             guid,
+             id,
+             parent_id,
+             component_count,
+             unknown,
+             prefab_id,
+         ): (&[u8], i32, i32, u16, i16, i32)|{
+            StandardGameObjectInfo {
+                guid: GUID {
+                    uuid: guid.try_into().unwrap()
+                },
+                id,
+                parent_id,
+                component_count,
+                unknown,
+                prefab_id,
+            }
+        }
+    )(input)
+}
+
+pub fn write_standard_gobject_info(info: &StandardGameObjectInfo, bytes: &mut Vec<u8>) {
+    bytes.write_all(&info.guid.uuid).unwrap();
+    bytes.write_all(&info.id.to_le_bytes()).unwrap();
+    bytes.write_all(&info.parent_id.to_le_bytes()).unwrap();
+    bytes.write_all(&info.component_count.to_le_bytes()).unwrap();
+    bytes.write_all(&info.unknown.to_le_bytes()).unwrap();
+    bytes.write_all(&info.prefab_id.to_le_bytes()).unwrap();
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FolderInfo {
+    pub id: i32,
+    pub parent_id: i32,
+}
+
+pub fn parse_folder_info(input: &[u8]) -> IResult<&[u8], FolderInfo> {
+    map(
+        tuple((
+            le_i32,
+            le_i32,
+        )),
+        |(
+             id,
+             parent_id,
+         )|{
+            FolderInfo {
+                id,
+                parent_id,
+            }
+        }
+    )(input)
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct GameObjectRefInfo {
     pub object_id: i32,
@@ -865,11 +1248,11 @@ pub struct ResourceInfo {
 }
 
 pub fn parse_resource_info_sf6(input: &[u8], offset: usize) -> IResult<&[u8], ResourceInfo> {
-    let remainder = &input[offset..];
-    let (remainder, str_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+    let remainder = input.get(offset..).ok_or_else(|| verify_error(input))?;
+    let (remainder, str_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder)?;
 
-    let str_remainder = &input[str_offset as usize..];
-    let (_, mut string) = map(take_until::<&str, &[u8], nom::error::Error<&[u8]>>("\0\0"), lossy_to_str)(str_remainder).unwrap();
+    let str_remainder = input.get(str_offset as usize..).ok_or_else(|| verify_error(input))?;
+    let (_, mut string) = map(take_until::<&str, &[u8], nom::error::Error<&[u8]>>("\0\0"), lossy_to_str)(str_remainder)?;
     string = string.replace("\u{0}", "");
 
     Ok((remainder, ResourceInfo {
@@ -879,24 +1262,27 @@ pub fn parse_resource_info_sf6(input: &[u8], offset: usize) -> IResult<&[u8], Re
 }
 
 pub fn parse_resource_info_dmc5(input: &[u8], offset: usize) -> IResult<&[u8], ResourceInfo> {
-    let remainder = &input[offset..];
+    let remainder = input.get(offset..).ok_or_else(|| verify_error(input))?;
 
-    let str_remainder = &input[offset as usize..];
-    let (_, mut string) = map(take_until::<&str, &[u8], nom::error::Error<&[u8]>>("\0\0"), lossy_to_str)(str_remainder).unwrap();
-    string = string.replace("\u{0}", "");
+    let str_remainder = input.get(offset..).ok_or_else(|| verify_error(input))?;
+    let (_, string_bytes) = take_until::<&str, &[u8], nom::error::Error<&[u8]>>("\0\0")(str_remainder)?;
+    let string = lossy_to_str(string_bytes).replace("\u{0}", "");
+    //the path is stored inline as UTF-16, the next one starts after its terminator
+    let string_size = string_bytes.len().div_ceil(2) * 2 + 2;
+    let remainder = &remainder[string_size.min(remainder.len())..];
 
     Ok((remainder, ResourceInfo {
         str_offset:None,
         string,
     }))
 }
-pub fn parse_resource_info(input: &[u8], offset: usize,is16version:bool) -> IResult<&[u8], ResourceInfo> {
-    match is16version{
+pub fn parse_resource_info<'a>(input: &'a [u8], offset: usize, profile: &GameProfile) -> IResult<&'a [u8], ResourceInfo> {
+    match profile.inline_resource_paths {
         true=>parse_resource_info_dmc5(input, offset),
         false=>parse_resource_info_sf6(input, offset),
     }
 }
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Clone)]
 pub struct RSZHeader {
     #[serde(skip)]
     pub magic: u32,
@@ -917,7 +1303,7 @@ pub struct RSZHeader {
     pub userdata_offset: i64,
 }
 
-fn parse_rsz_header(input: &[u8]) -> IResult<&[u8], RSZHeader> {
+fn parse_rsz_header_userdata(input: &[u8]) -> IResult<&[u8], RSZHeader> {
     map(
         tuple((
             le_u32,
@@ -956,7 +1342,49 @@ fn parse_rsz_header(input: &[u8]) -> IResult<&[u8], RSZHeader> {
     )(input)
 }
 
-#[derive(Serialize, Deserialize)]
+//Versions before userdata existed have no userdata count or offset
+fn parse_rsz_header_no_userdata(input: &[u8]) -> IResult<&[u8], RSZHeader> {
+    map(
+        tuple((
+            le_u32,
+            le_u32,
+            le_i32,
+            le_i32,
+            le_i64,
+            le_i64,
+        )),
+        |(
+             magic,
+             version,
+             object_count,
+             instance_count,
+             instance_offsets,
+             data_offset,
+         )|{
+            RSZHeader {
+                magic,
+                version,
+                object_count,
+                instance_count,
+                userdata_count: 0,
+                reserved: 0,
+                instance_offsets,
+                data_offset,
+                userdata_offset: 0,
+            }
+        }
+    )(input)
+}
+
+fn parse_rsz_header(input: &[u8]) -> IResult<&[u8], RSZHeader> {
+    let (_, version) = le_u32(input.get(4..).ok_or_else(|| verify_error(input))?)?;
+    match rsz_layout(version).userdata {
+        UserDataLayout::None => parse_rsz_header_no_userdata(input),
+        _ => parse_rsz_header_userdata(input),
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
 pub struct RSZ {
     pub header: RSZHeader,
     #[serde(skip)]
@@ -967,24 +1395,91 @@ pub struct RSZ {
     pub data: Vec<RSZData>,
 }
 
+impl RSZ {
+    //Instance 0 is always null and userdata instances have no data, so they are skipped when indexing
+    pub fn data_index(&self, instance_id: i32) -> Option<usize> {
+        if instance_id <= 0 || self.userdata_infos.iter().any(|userdata| userdata.instance_id as i32 == instance_id) {
+            return None;
+        }
+        let userdata_before = self.userdata_infos.iter()
+            .filter(|userdata| (userdata.instance_id as i32) < instance_id)
+            .count();
+        let index = instance_id as usize - 1 - userdata_before;
+        match index < self.data.len() {
+            true => Some(index),
+            false => None,
+        }
+    }
+
+    pub fn instance_data(&self, instance_id: i32) -> Option<&RSZData> {
+        self.data.get(self.data_index(instance_id)?)
+    }
+
+    pub fn instance_data_mut(&mut self, instance_id: i32) -> Option<&mut RSZData> {
+        let index = self.data_index(instance_id)?;
+        self.data.get_mut(index)
+    }
+
+    //The instances listed in the object table, in order
+    pub fn root_data(&self) -> Vec<&RSZData> {
+        self.object_table.iter().filter_map(|instance_id| self.instance_data(*instance_id)).collect()
+    }
+
+    //Userdata instances have no data of their own, embedded ones hold a nested RSZ instead
+    pub fn userdata_rsz(&self, instance_id: i32) -> Option<&RSZ> {
+        self.userdata_infos.iter()
+            .find(|userdata| userdata.instance_id as i32 == instance_id)?
+            .rsz.as_ref()
+    }
+}
+
+//Files of the same version can still use path userdata, so the first entry has to point at an RSZ block
+fn has_embedded_userdata(header: &RSZHeader, rsz_input: &[u8], userdata_offset: usize) -> bool {
+    if rsz_layout(header.version).userdata != UserDataLayout::Embeddable || header.userdata_count <= 0 {
+        return false;
+    }
+    let rsz_offset = match rsz_input.get(userdata_offset + 16..userdata_offset + 24) {
+        Some(bytes) => u64::from_le_bytes(bytes.try_into().unwrap()) as usize,
+        None => return false,
+    };
+    rsz_input.get(rsz_offset..rsz_offset + 4) == Some(&RSZ_MAGIC.to_le_bytes()[..])
+}
+
+pub(crate) fn verify_error(input: &[u8]) -> nom::Err<nom::error::Error<&[u8]>> {
+    nom::Err::Failure(nom::error::Error::new(input, nom::error::ErrorKind::Verify))
+}
+
 pub fn parse_rsz(input: &[u8], offset: usize) -> IResult<&[u8], RSZ> {
-    let orig_remainder = &input[offset..];
-    let (orig_remainder, header) = parse_rsz_header(orig_remainder).unwrap();
-    let (orig_remainder, object_table) = count(le_i32::<&[u8], nom::error::Error<&[u8]>>, header.object_count as usize)(orig_remainder).unwrap();
-    let (mut remainder, instance_infos) = count(parse_instance_info, header.instance_count as usize)(orig_remainder).unwrap();
+    let rsz_offset = offset;
+    let orig_remainder = input.get(offset..).ok_or_else(|| verify_error(input))?;
+    let (orig_remainder, header) = parse_rsz_header(orig_remainder)?;
+    let (orig_remainder, object_table) = count(le_i32::<&[u8], nom::error::Error<&[u8]>>, header.object_count as usize)(orig_remainder)?;
+    let layout = rsz_layout(header.version);
+    let (mut remainder, instance_infos) = count(
+        |input| parse_instance_info(input, layout.instance_info_size),
+        header.instance_count as usize,
+    )(orig_remainder)?;
     let alignment_remainder = (16 -(input.len() - remainder.len()) % 16) % 16;
     if alignment_remainder != 0 {
-        remainder = &remainder[alignment_remainder..];
+        remainder = remainder.get(alignment_remainder..).ok_or_else(|| verify_error(input))?;
     }
     let mut userdata_infos: Vec<UserDataInfo> = vec![];
+    //userdata paths and embedded blocks are stored relative to the RSZ header
+    let rsz_input = input.get(rsz_offset..).ok_or_else(|| verify_error(input))?;
+    let embedded = has_embedded_userdata(&header, rsz_input, input.len() - remainder.len() - rsz_offset);
     for _ in 0..header.userdata_count {
         let offset = input.len() - remainder.len();
-        let (new_remainder, userdata_info) = parse_userdata_info(input, offset).unwrap();
+        let (new_remainder, userdata_info) = match embedded {
+            true => parse_embedded_userdata_info(rsz_input, offset - rsz_offset)?,
+            false => parse_userdata_info(rsz_input, offset - rsz_offset)?,
+        };
         remainder = new_remainder;
         userdata_infos.push(userdata_info);
     }
     let mut datas: Vec<RSZData> = vec![];
-    remainder = &input[header.data_offset as usize + offset..];
+    remainder = (header.data_offset as usize).checked_add(offset)
+        .and_then(|data_offset| input.get(data_offset..))
+        .ok_or_else(|| verify_error(input))?;
     'outer: for n in 1..header.instance_count {
         for userdata in &userdata_infos {
             if n == userdata.instance_id as i32 {
@@ -992,7 +1487,7 @@ pub fn parse_rsz(input: &[u8], offset: usize) -> IResult<&[u8], RSZ> {
             }
         }
         let new_offset = input.len() - remainder.len();
-        let (remainder_new, cur_data) = parse_rsz_data(input, new_offset, instance_infos[n as usize].hash.clone()).unwrap();
+        let (remainder_new, cur_data) = parse_rsz_data(input, new_offset, instance_infos[n as usize].hash)?;
         datas.push(cur_data);
         remainder = remainder_new;
     }
@@ -1006,4 +1501,108 @@ pub fn parse_rsz(input: &[u8], offset: usize) -> IResult<&[u8], RSZ> {
             data: datas
         }
     ))
-}
\ No newline at end of file
+}
+
+//Reads whole UTF-16 units up to the 0 terminator write_utf16 puts after a string
+pub fn read_utf16(input: &[u8]) -> String {
+    let units: Vec<u16> = input.chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .take_while(|unit| *unit != 0)
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+pub fn write_utf16(string: &str, bytes: &mut Vec<u8>) {
+    for char in string.encode_utf16() {
+        bytes.write_all(&char.to_le_bytes()).unwrap();
+    }
+    bytes.write_all(&[0; 2]).unwrap();
+}
+
+pub fn align_to(bytes: &mut Vec<u8>, alignment: usize) {
+    let padding = (alignment - bytes.len() % alignment) % alignment;
+    bytes.resize(bytes.len() + padding, 0);
+}
+
+pub fn align_to_16(bytes: &mut Vec<u8>) {
+    align_to(bytes, 16);
+}
+
+//Appends the RSZ block to the file written so far, the header counts and offsets are taken from the tables
+pub fn write_rsz(rsz: &RSZ, bytes: &mut Vec<u8>) {
+    let layout = rsz_layout(rsz.header.version);
+    let start = bytes.len();
+    bytes.resize(start + layout.header_size, 0);
+    for instance_id in &rsz.object_table {
+        bytes.write_all(&instance_id.to_le_bytes()).unwrap();
+    }
+    let instance_offsets = (bytes.len() - start) as i64;
+    for instance_info in &rsz.instance_infos {
+        bytes.write_all(&instance_info.hash.to_le_bytes()).unwrap();
+        bytes.write_all(&instance_info.crc.to_le_bytes()).unwrap();
+        bytes.resize(bytes.len() + layout.instance_info_size - 8, 0);
+    }
+    align_to_16(bytes);
+    let userdata_offset = (bytes.len() - start) as i64;
+    let userdata_table = bytes.len();
+    match rsz.userdata_infos.iter().any(|userdata_info| userdata_info.rsz.is_some()) {
+        true => {
+            bytes.resize(userdata_table + layout.embedded_userdata_info_size * rsz.userdata_infos.len(), 0);
+            for (n, userdata_info) in rsz.userdata_infos.iter().enumerate() {
+                align_to_16(bytes);
+                let rsz_offset = (bytes.len() - start) as u64;
+                //nested blocks are aligned from their own start, so they are written on their own first
+                let mut nested: Vec<u8> = vec![];
+                if let Some(nested_rsz) = &userdata_info.rsz {
+                    write_rsz(nested_rsz, &mut nested);
+                }
+                bytes.write_all(&nested).unwrap();
+                let entry = userdata_table + layout.embedded_userdata_info_size * n;
+                bytes[entry..entry + 4].copy_from_slice(&userdata_info.instance_id.to_le_bytes());
+                bytes[entry + 4..entry + 8].copy_from_slice(&userdata_info.type_id.to_le_bytes());
+                bytes[entry + 8..entry + 12].copy_from_slice(&userdata_info.json_path_hash.unwrap_or(0).to_le_bytes());
+                bytes[entry + 12..entry + 16].copy_from_slice(&(nested.len() as u32).to_le_bytes());
+                bytes[entry + 16..entry + 24].copy_from_slice(&rsz_offset.to_le_bytes());
+            }
+        }
+        false => {
+            bytes.resize(userdata_table + layout.userdata_info_size * rsz.userdata_infos.len(), 0);
+            for (n, userdata_info) in rsz.userdata_infos.iter().enumerate() {
+                let str_offset = (bytes.len() - start) as u64;
+                write_utf16(&userdata_info.string, bytes);
+                let entry = userdata_table + layout.userdata_info_size * n;
+                bytes[entry..entry + 4].copy_from_slice(&userdata_info.instance_id.to_le_bytes());
+                bytes[entry + 4..entry + 8].copy_from_slice(&userdata_info.type_id.to_le_bytes());
+                bytes[entry + 8..entry + 16].copy_from_slice(&str_offset.to_le_bytes());
+            }
+        }
+    }
+    align_to_16(bytes);
+    let data_offset = (bytes.len() - start) as i64;
+    let mut datas = rsz.data.iter();
+    for (n, instance_info) in rsz.instance_infos.iter().enumerate().skip(1) {
+        if rsz.userdata_infos.iter().any(|userdata| userdata.instance_id as usize == n) {
+            continue;
+        }
+        if let Some(data) = datas.next() {
+            write_rsz_data(data, instance_info.hash, bytes);
+        }
+    }
+
+    let has_userdata = layout.userdata != UserDataLayout::None;
+    let mut header: Vec<u8> = vec![];
+    header.write_all(&rsz.header.magic.to_le_bytes()).unwrap();
+    header.write_all(&rsz.header.version.to_le_bytes()).unwrap();
+    header.write_all(&(rsz.object_table.len() as i32).to_le_bytes()).unwrap();
+    header.write_all(&(rsz.instance_infos.len() as i32).to_le_bytes()).unwrap();
+    if has_userdata {
+        header.write_all(&(rsz.userdata_infos.len() as i32).to_le_bytes()).unwrap();
+        header.write_all(&rsz.header.reserved.to_le_bytes()).unwrap();
+    }
+    header.write_all(&instance_offsets.to_le_bytes()).unwrap();
+    header.write_all(&data_offset.to_le_bytes()).unwrap();
+    if has_userdata {
+        header.write_all(&userdata_offset.to_le_bytes()).unwrap();
+    }
+    bytes[start..start + layout.header_size].copy_from_slice(&header);
+}
@@ -269,6 +269,21 @@ pub fn get_field_array_state(class_hash: &u32, field_index: &usize) -> RSZResult
     }
 }
 
+//Names of the fields of every class in the schema
+pub fn get_field_names() -> Vec<String> {
+    let json = JSON.lock().unwrap();
+    let classes = match json.as_object() {
+        Some(classes) => classes,
+        None => return vec![],
+    };
+    classes.values()
+        .filter_map(|class| class.get("fields")?.as_array())
+        .flatten()
+        .filter_map(|field| field.get("name")?.as_str())
+        .map(|name| name.to_string())
+        .collect()
+}
+
 pub fn get_rsz_class_name(class_hash: &u32) -> RSZResult<String>
 {
     let class_key = format!("{:x}", class_hash);
@@ -0,0 +1,53 @@
+//RSZ header versions found in the files of each title
+pub const DMC5_VERSION: u32 = 8;
+pub const RE2_VERSION: u32 = 8;
+pub const RE3_VERSION: u32 = 8;
+pub const RE8_VERSION: u32 = 10;
+pub const MHRISE_VERSION: u32 = 10;
+pub const SF6_VERSION: u32 = 16;
+pub const RE4_VERSION: u32 = 16;
+
+//Headers older than this have no userdata count or userdata table offset
+pub const USERDATA_VERSION: u32 = 4;
+//The first version seen with userdata embedded as nested RSZ blocks
+pub const EMBEDDED_USERDATA_VERSION: u32 = 16;
+
+//Size of headers with a userdata count and table offset, the largest header of any version
+pub const RSZ_HEADER_SIZE: usize = 48;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UserDataLayout {
+    None,
+    //instance id, type id and the offset of a .user path
+    Path,
+    //files of these versions can still use paths, embedded blocks are told apart by their RSZ magic
+    Embeddable,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RSZLayout {
+    pub header_size: usize,
+    //every supported version stores a hash and crc per instance
+    pub instance_info_size: usize,
+    pub userdata_info_size: usize,
+    pub embedded_userdata_info_size: usize,
+    pub userdata: UserDataLayout,
+}
+
+pub fn rsz_layout(version: u32) -> RSZLayout {
+    let userdata = match version {
+        version if version < USERDATA_VERSION => UserDataLayout::None,
+        version if version < EMBEDDED_USERDATA_VERSION => UserDataLayout::Path,
+        _ => UserDataLayout::Embeddable,
+    };
+    RSZLayout {
+        header_size: match userdata {
+            UserDataLayout::None => 32,
+            _ => RSZ_HEADER_SIZE,
+        },
+        instance_info_size: 8,
+        userdata_info_size: 16,
+        embedded_userdata_info_size: 24,
+        userdata,
+    }
+}
@@ -0,0 +1,252 @@
+use nom::{
+    combinator::*,
+    sequence::tuple,
+    IResult,
+};
+use nom::bytes::complete::take_until;
+use nom::multi::count;
+use nom::number::complete::{le_i32, le_u32, le_u64};
+use std::io::Write;
+use serde::{Deserialize, Serialize};
+
+use crate::prefab::tree::{gameobject_tree, PrefabTree};
+use crate::profile::GameProfile;
+use crate::rsz::{parse_rsz, write_rsz, write_utf16, align_to, lossy_to_str, RSZ, GameObjectInfo, StandardGameObjectInfo, FolderInfo, UserDataInfo, ResourceInfo, parse_standard_gobject_info, write_standard_gobject_info, parse_folder_info, parse_resource_info, parse_userdata_info};
+
+#[derive(Serialize, Deserialize)]
+pub struct SceneHeader {
+    #[serde(skip)]
+    pub magic: u32,
+    pub info_count: i32, //How many GameObjects the scene holds
+    pub resource_count: i32, //how many external files are referenced
+    pub folder_count: i32,
+    pub prefab_count: i32, //how many prefabs GameObjects are spawned from
+    pub userdata_count: i32,
+    #[serde(skip)]
+    pub folder_tbl: u64,
+    #[serde(skip)]
+    pub resource_info_tbl: u64,
+    #[serde(skip)]
+    pub prefab_info_tbl: u64,
+    #[serde(skip)]
+    pub userdata_info_tbl: u64,
+    #[serde(skip)]
+    pub data_offset: u64, //offset of the main RSZ header
+}
+
+fn parse_scene_header(input: &[u8]) -> IResult<&[u8], SceneHeader>
+{
+    map(
+        tuple((
+            le_u32,
+            le_i32,
+            le_i32,
+            le_i32,
+            le_i32,
+            le_i32,
+            le_u64,
+            le_u64,
+            le_u64,
+            le_u64,
+            le_u64,
+        )),
+        |(
+             magic,
+             info_count,
+             resource_count,
+             folder_count,
+             prefab_count,
+             userdata_count,
+             folder_tbl,
+             resource_info_tbl,
+             prefab_info_tbl,
+             userdata_info_tbl,
+             data_offset,
+         )|{
+            SceneHeader {
+                magic,
+                info_count,
+                resource_count,
+                folder_count,
+                prefab_count,
+                userdata_count,
+                folder_tbl,
+                resource_info_tbl,
+                prefab_info_tbl,
+                userdata_info_tbl,
+                data_offset,
+            }
+        }
+    )(input)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PrefabInfo {
+    #[serde(skip)]
+    pub str_offset: u32,
+    pub parent_id: i32,
+    pub string: String,
+}
+
+fn parse_prefab_info(input: &[u8], offset: usize) -> IResult<&[u8], PrefabInfo> {
+    let remainder = &input[offset..];
+    let (remainder, str_offset) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+    let (remainder, parent_id) = le_i32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+
+    let str_remainder = &input[str_offset as usize..];
+    let (_, mut string) = map(take_until::<&str, &[u8], nom::error::Error<&[u8]>>("\0\0"), lossy_to_str)(str_remainder).unwrap();
+    string = string.replace("\u{0}", "");
+
+    Ok((remainder, PrefabInfo {
+        str_offset,
+        parent_id,
+        string,
+    }))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Scene {
+    pub header: SceneHeader,
+    pub gameobject_infos: Vec<StandardGameObjectInfo>,
+    pub folder_infos: Vec<FolderInfo>,
+    pub resource_infos: Vec<ResourceInfo>,
+    pub prefab_infos: Vec<PrefabInfo>,
+    pub userdata_infos: Vec<UserDataInfo>,
+    pub gameobject: RSZ,
+}
+
+//SF6 (.scn.20) and DMC5 (.scn.19) share the header, DMC5 stores resource paths inline like .pfb.16
+pub fn parse_scene<'a>(input: &'a [u8], profile: &GameProfile) -> IResult<&'a [u8], Scene> {
+    let (remainder, header) = parse_scene_header(input).unwrap();
+    let (_, gameobject_infos) = count(parse_standard_gobject_info, header.info_count as usize)(remainder).unwrap();
+    let (_, folder_infos) = count(parse_folder_info, header.folder_count as usize)(&input[header.folder_tbl as usize..]).unwrap();
+    let mut resource_infos: Vec<ResourceInfo> = vec![];
+    let mut remainder = &input[header.resource_info_tbl as usize..];
+    for _ in 0..header.resource_count {
+        let offset = input.len() - remainder.len();
+        let (new_remainder, resource_info) = parse_resource_info(input, offset, profile).unwrap();
+        remainder = new_remainder;
+        resource_infos.push(resource_info);
+    }
+    let mut prefab_infos: Vec<PrefabInfo> = vec![];
+    for n in 0..header.prefab_count as usize {
+        let (_, prefab_info) = parse_prefab_info(input, header.prefab_info_tbl as usize + n * 8).unwrap();
+        prefab_infos.push(prefab_info);
+    }
+    let mut userdata_infos: Vec<UserDataInfo> = vec![];
+    for n in 0..header.userdata_count as usize {
+        let (_, userdata_info) = parse_userdata_info(input, header.userdata_info_tbl as usize + n * 16).unwrap();
+        userdata_infos.push(userdata_info);
+    }
+
+    //Main game object parsed here.
+    let (remainder, gameobject) = parse_rsz(input, header.data_offset as usize).unwrap();
+
+    Ok((
+        remainder, Scene {
+            header,
+            gameobject_infos,
+            folder_infos,
+            resource_infos,
+            prefab_infos,
+            userdata_infos,
+            gameobject,
+        }
+    ))
+}
+
+//Tables are laid out again from scratch, so the counts and offsets in the header are recalculated
+pub fn write_scene(scene: &Scene, profile: &GameProfile) -> Vec<u8> {
+    let mut bytes: Vec<u8> = vec![0; 64];
+    for gameobject_info in &scene.gameobject_infos {
+        write_standard_gobject_info(gameobject_info, &mut bytes);
+    }
+    align_to(&mut bytes, profile.table_alignment);
+    let folder_tbl = bytes.len() as u64;
+    for folder_info in &scene.folder_infos {
+        bytes.write_all(&folder_info.id.to_le_bytes()).unwrap();
+        bytes.write_all(&folder_info.parent_id.to_le_bytes()).unwrap();
+    }
+    align_to(&mut bytes, profile.table_alignment);
+    let resource_info_tbl = bytes.len();
+    match profile.inline_resource_paths {
+        true => scene.resource_infos.iter().for_each(|resource_info| write_utf16(&resource_info.string, &mut bytes)),
+        false => bytes.resize(resource_info_tbl + 8 * scene.resource_infos.len(), 0),
+    }
+    align_to(&mut bytes, profile.table_alignment);
+    let prefab_info_tbl = bytes.len();
+    bytes.resize(prefab_info_tbl + 8 * scene.prefab_infos.len(), 0);
+    align_to(&mut bytes, profile.table_alignment);
+    let userdata_info_tbl = bytes.len();
+    bytes.resize(userdata_info_tbl + 16 * scene.userdata_infos.len(), 0);
+
+    //the path strings follow the tables, the tables point at them with absolute offsets
+    if !profile.inline_resource_paths {
+        for (n, resource_info) in scene.resource_infos.iter().enumerate() {
+            let str_offset = bytes.len() as u64;
+            write_utf16(&resource_info.string, &mut bytes);
+            let entry = resource_info_tbl + 8 * n;
+            bytes[entry..entry + 8].copy_from_slice(&str_offset.to_le_bytes());
+        }
+    }
+    for (n, prefab_info) in scene.prefab_infos.iter().enumerate() {
+        let str_offset = bytes.len() as u32;
+        write_utf16(&prefab_info.string, &mut bytes);
+        let entry = prefab_info_tbl + 8 * n;
+        bytes[entry..entry + 4].copy_from_slice(&str_offset.to_le_bytes());
+        bytes[entry + 4..entry + 8].copy_from_slice(&prefab_info.parent_id.to_le_bytes());
+    }
+    for (n, userdata_info) in scene.userdata_infos.iter().enumerate() {
+        let str_offset = bytes.len() as u64;
+        write_utf16(&userdata_info.string, &mut bytes);
+        let entry = userdata_info_tbl + 16 * n;
+        bytes[entry..entry + 4].copy_from_slice(&userdata_info.instance_id.to_le_bytes());
+        bytes[entry + 4..entry + 8].copy_from_slice(&userdata_info.type_id.to_le_bytes());
+        bytes[entry + 8..entry + 16].copy_from_slice(&str_offset.to_le_bytes());
+    }
+    align_to(&mut bytes, profile.table_alignment);
+    let data_offset = bytes.len() as u64;
+    write_rsz(&scene.gameobject, &mut bytes);
+
+    let mut header: Vec<u8> = vec![];
+    header.write_all(&scene.header.magic.to_le_bytes()).unwrap();
+    header.write_all(&(scene.gameobject_infos.len() as i32).to_le_bytes()).unwrap();
+    header.write_all(&(scene.resource_infos.len() as i32).to_le_bytes()).unwrap();
+    header.write_all(&(scene.folder_infos.len() as i32).to_le_bytes()).unwrap();
+    header.write_all(&(scene.prefab_infos.len() as i32).to_le_bytes()).unwrap();
+    header.write_all(&(scene.userdata_infos.len() as i32).to_le_bytes()).unwrap();
+    header.write_all(&folder_tbl.to_le_bytes()).unwrap();
+    header.write_all(&(resource_info_tbl as u64).to_le_bytes()).unwrap();
+    header.write_all(&(prefab_info_tbl as u64).to_le_bytes()).unwrap();
+    header.write_all(&(userdata_info_tbl as u64).to_le_bytes()).unwrap();
+    header.write_all(&data_offset.to_le_bytes()).unwrap();
+    bytes[0..64].copy_from_slice(&header);
+    bytes
+}
+
+impl Scene {
+    //prefab_id indexes the prefab table, GameObjects placed directly in the scene have -1
+    pub fn gameobject_prefab(&self, info: &StandardGameObjectInfo) -> Option<&PrefabInfo> {
+        self.prefab_infos.get(usize::try_from(info.prefab_id).ok()?)
+    }
+
+    //Folders are placed in the hierarchy like GameObjects without components
+    pub fn tree(&self) -> PrefabTree<'_> {
+        let mut infos: Vec<GameObjectInfo> = self.gameobject_infos.iter()
+            .map(|info| GameObjectInfo {
+                id: info.id,
+                parent_id: info.parent_id,
+                component_count: info.component_count as i32,
+            })
+            .collect();
+        infos.extend(self.folder_infos.iter().map(|folder| GameObjectInfo {
+            id: folder.id,
+            parent_id: folder.parent_id,
+            component_count: 0,
+        }));
+        let prefab_paths: Vec<Option<&str>> = self.gameobject_infos.iter()
+            .map(|info| self.gameobject_prefab(info).map(|prefab| prefab.string.as_str()))
+            .collect();
+        gameobject_tree(&infos, &prefab_paths, &self.gameobject)
+    }
+}
@@ -0,0 +1,48 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::profile::GameProfile;
+use crate::rsz::json_parser::parse_json;
+
+#[derive(Debug, Clone)]
+pub struct SchemaError(pub String);
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Schema error: {}", self.0)
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaSource {
+    //one of the dumps compiled into the binary, by file name
+    Embedded(&'static str),
+    File(PathBuf),
+}
+
+impl fmt::Display for SchemaSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaSource::Embedded(name) => write!(f, "embedded {}", name),
+            SchemaSource::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+//Dumps from a new patch can be used right away without rebuilding the parser
+pub fn load_schema_file(path: &Path) -> Result<SchemaSource, Box<dyn std::error::Error>> {
+    let json_bytes = std::fs::read(path)
+        .map_err(|error| SchemaError(format!("could not read {}: {}", path.display(), error)))?;
+    parse_json(json_bytes)?;
+    Ok(SchemaSource::File(path.to_path_buf()))
+}
+
+//The dumps are compiled into the executable, not the library, so the bytes are handed in by the caller
+pub fn load_schema_bytes(profile: &GameProfile, json_bytes: Vec<u8>) -> Result<SchemaSource, Box<dyn std::error::Error>> {
+    let name = profile.schema
+        .ok_or_else(|| SchemaError(format!("no schema dump is known for {:?}", profile.game)))?;
+    parse_json(json_bytes)?;
+    Ok(SchemaSource::Embedded(name))
+}
@@ -0,0 +1,169 @@
+use nom::{
+    combinator::*,
+    sequence::tuple,
+    IResult,
+};
+use nom::bytes::complete::take_until;
+use nom::number::complete::{le_i32, le_u32, le_u64};
+use std::io::Write;
+use serde::{Deserialize, Serialize};
+
+use crate::profile::GameProfile;
+use crate::rsz::{parse_rsz, write_rsz, write_utf16, align_to, lossy_to_str, parse_resource_info, ResourceInfo, RSZ};
+
+#[derive(Serialize, Deserialize)]
+pub struct UserDataHeader {
+    #[serde(skip)]
+    pub magic: u32,
+    pub resource_count: i32, //how many external files are referenced
+    pub userdata_count: i32, //how many other .user files are referenced
+    pub info_count: i32,
+    #[serde(skip)]
+    pub resource_info_tbl: u64,
+    #[serde(skip)]
+    pub userdata_info_tbl: u64,
+    #[serde(skip)]
+    pub data_offset: u64, //offset of the main RSZ header
+}
+
+fn parse_user_header(input: &[u8]) -> IResult<&[u8], UserDataHeader>
+{
+    map(
+        tuple((
+            le_u32,
+            le_i32,
+            le_i32,
+            le_i32,
+            le_u64,
+            le_u64,
+            le_u64,
+        )),
+        |(
+             magic,
+             resource_count,
+             userdata_count,
+             info_count,
+             resource_info_tbl,
+             userdata_info_tbl,
+             data_offset,
+         )|{
+            UserDataHeader {
+                magic,
+                resource_count,
+                userdata_count,
+                info_count,
+                resource_info_tbl,
+                userdata_info_tbl,
+                data_offset,
+            }
+        }
+    )(input)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserDataInfo {
+    pub hash: u32,
+    pub crc: u32,
+    #[serde(skip)]
+    pub str_offset: u64,
+    pub string: String,
+}
+
+fn parse_user_userdata_info(input: &[u8], offset: usize) -> IResult<&[u8], UserDataInfo> {
+    let remainder = &input[offset..];
+    let (remainder, hash) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+    let (remainder, crc) = le_u32::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+    let (remainder, str_offset) = le_u64::<&[u8], nom::error::Error<&[u8]>>(remainder).unwrap();
+
+    let str_remainder = &input[str_offset as usize..];
+    let (_, mut string) = map(take_until::<&str, &[u8], nom::error::Error<&[u8]>>("\0\0"), lossy_to_str)(str_remainder).unwrap();
+    string = string.replace("\u{0}", "");
+
+    Ok((remainder, UserDataInfo {
+        hash,
+        crc,
+        str_offset,
+        string,
+    }))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserData {
+    pub header: UserDataHeader,
+    pub resource_infos: Vec<ResourceInfo>,
+    pub userdata_infos: Vec<UserDataInfo>,
+    pub rsz: RSZ,
+}
+
+pub fn parse_user<'a>(input: &'a [u8], profile: &GameProfile) -> IResult<&'a [u8], UserData> {
+    let (_, header) = parse_user_header(input).unwrap();
+    let mut resource_infos: Vec<ResourceInfo> = vec![];
+    let mut remainder = &input[header.resource_info_tbl as usize..];
+    for _ in 0..header.resource_count {
+        let offset = input.len() - remainder.len();
+        let (new_remainder, resource_info) = parse_resource_info(input, offset, profile).unwrap();
+        remainder = new_remainder;
+        resource_infos.push(resource_info);
+    }
+    let mut userdata_infos: Vec<UserDataInfo> = vec![];
+    for n in 0..header.userdata_count as usize {
+        let (_, userdata_info) = parse_user_userdata_info(input, header.userdata_info_tbl as usize + n * 16).unwrap();
+        userdata_infos.push(userdata_info);
+    }
+    let (remainder, rsz) = parse_rsz(input, header.data_offset as usize).unwrap();
+
+    Ok((
+        remainder, UserData {
+            header,
+            resource_infos,
+            userdata_infos,
+            rsz,
+        }
+    ))
+}
+
+//Tables are laid out again from scratch, so the counts and offsets in the header are recalculated
+pub fn write_user(user: &UserData, profile: &GameProfile) -> Vec<u8> {
+    let mut bytes: Vec<u8> = vec![0; 40];
+    align_to(&mut bytes, profile.table_alignment);
+    let resource_info_tbl = bytes.len();
+    match profile.inline_resource_paths {
+        true => user.resource_infos.iter().for_each(|resource_info| write_utf16(&resource_info.string, &mut bytes)),
+        false => bytes.resize(resource_info_tbl + 8 * user.resource_infos.len(), 0),
+    }
+    align_to(&mut bytes, profile.table_alignment);
+    let userdata_info_tbl = bytes.len();
+    bytes.resize(userdata_info_tbl + 16 * user.userdata_infos.len(), 0);
+
+    //the path strings follow the tables, the tables point at them with absolute offsets
+    if !profile.inline_resource_paths {
+        for (n, resource_info) in user.resource_infos.iter().enumerate() {
+            let str_offset = bytes.len() as u64;
+            write_utf16(&resource_info.string, &mut bytes);
+            let entry = resource_info_tbl + 8 * n;
+            bytes[entry..entry + 8].copy_from_slice(&str_offset.to_le_bytes());
+        }
+    }
+    for (n, userdata_info) in user.userdata_infos.iter().enumerate() {
+        let str_offset = bytes.len() as u64;
+        write_utf16(&userdata_info.string, &mut bytes);
+        let entry = userdata_info_tbl + 16 * n;
+        bytes[entry..entry + 4].copy_from_slice(&userdata_info.hash.to_le_bytes());
+        bytes[entry + 4..entry + 8].copy_from_slice(&userdata_info.crc.to_le_bytes());
+        bytes[entry + 8..entry + 16].copy_from_slice(&str_offset.to_le_bytes());
+    }
+    align_to(&mut bytes, profile.table_alignment);
+    let data_offset = bytes.len() as u64;
+    write_rsz(&user.rsz, &mut bytes);
+
+    let mut header: Vec<u8> = vec![];
+    header.write_all(&user.header.magic.to_le_bytes()).unwrap();
+    header.write_all(&(user.resource_infos.len() as i32).to_le_bytes()).unwrap();
+    header.write_all(&(user.userdata_infos.len() as i32).to_le_bytes()).unwrap();
+    header.write_all(&user.header.info_count.to_le_bytes()).unwrap();
+    header.write_all(&(resource_info_tbl as u64).to_le_bytes()).unwrap();
+    header.write_all(&(userdata_info_tbl as u64).to_le_bytes()).unwrap();
+    header.write_all(&data_offset.to_le_bytes()).unwrap();
+    bytes[0..40].copy_from_slice(&header);
+    bytes
+}
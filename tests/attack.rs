@@ -0,0 +1,33 @@
+mod common;
+
+use sf6_rsz_parser::fchar::attack::AttackField;
+use sf6_rsz_parser::fchar::{parse_fchar, write_fchar, DataId};
+use sf6_rsz_parser::profile::SF6;
+
+#[test]
+fn attack_data_lists_the_actions_referencing_each_entry() {
+    let character = common::character();
+    let attack_data = character.attack_data();
+    let rows: Vec<(u32, Option<f64>, Vec<i32>)> = attack_data.iter()
+        .map(|entry| (entry.id, entry.damage, entry.actions.clone()))
+        .collect();
+    assert_eq!(rows, vec![(0, Some(300.0), vec![600, 601]), (1, Some(600.0), vec![601])]);
+    assert_eq!(character.attack_references(DataId::AttackDataParams, 1), vec![601]);
+
+    let hits: Vec<(i32, u32)> = character.action_hits(601).iter().map(|(frame, entry)| (*frame, entry.id)).collect();
+    assert_eq!(hits, vec![(6, 1), (12, 0)]);
+}
+
+#[test]
+fn attack_values_are_set_and_written_back() {
+    common::load_schema();
+    let mut character = common::character();
+    assert!(character.set_attack_value(DataId::AttackDataParams, 1, AttackField::Damage, 700.0));
+    assert!(!character.set_attack_value(DataId::AttackDataParams, 2, AttackField::Damage, 700.0));
+    assert!(!character.set_attack_value(DataId::AttackDataParams, 1, AttackField::HitStop, 10.0));
+    assert!(!character.set_attack_value(DataId::AttackDataCommon, 0, AttackField::Damage, 700.0));
+
+    let (_, parsed) = parse_fchar(&write_fchar(&character, &SF6), &SF6).unwrap();
+    assert_eq!(parsed.attack_entry(DataId::AttackDataParams, 1).unwrap().damage, Some(700.0));
+    assert_eq!(parsed.attack_entry(DataId::AttackDataParams, 0).unwrap().damage, Some(300.0));
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use sf6_rsz_parser::fchar::{CharacterAsset, DataId};
+use sf6_rsz_parser::rsz::json_parser::TypeIDs;
+use sf6_rsz_parser::rsz::{RSZValue, UInt3};
+
+//A second action list entry reusing id 600, only the second one has a camera key
+fn character_with_camera_in_repeated_id() -> CharacterAsset {
+    let mut character = common::character();
+    let camera_key = common::data("test.CameraKey", vec![common::field("CameraDataIndex", TypeIDs::S32, RSZValue::Int32(0))]);
+    character.action_list.push(common::action(600, "5LP", vec![(common::key_data(2, 3), camera_key)]));
+    character.data_id_table.push(DataId::CameraData);
+    character.data_list_table.push(common::data_list(vec![0], vec![(0x40, common::data("test.Camera", vec![]))]));
+    character
+}
+
+#[test]
+fn every_action_list_entry_is_visited_for_cameras() {
+    let cameras = character_with_camera_in_repeated_id().action_cameras();
+    assert_eq!(cameras.len(), 1);
+    assert_eq!(cameras[0].action_id, 600);
+    assert_eq!(cameras[0].keyframes[0].frame, 2);
+}
+
+#[test]
+fn unsigned_vectors_take_components() {
+    let mut value = RSZValue::UInt3(UInt3 { x: 0, y: 0, z: 0 });
+    assert!(value.set_components(&[1.0, 2.4, 3.6]));
+    assert_eq!(value.components(), Some(vec![1.0, 2.0, 4.0]));
+    assert!(!value.set_components(&[1.0, 2.0]));
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use sf6_rsz_parser::fchar::{CharacterAsset, DataId};
+use sf6_rsz_parser::rsz::json_parser::TypeIDs;
+use sf6_rsz_parser::rsz::RSZValue;
+
+const TRIGGER_KEY_CLASS: u32 = 0x20;
+const TRIGGER_GROUP_CLASS: u32 = 0x21;
+const TRIGGER_CLASS: u32 = 0x22;
+
+//Action 600 cancels through trigger group 0, whose bitset has bits 0 and 2 set
+fn character_with_cancels() -> CharacterAsset {
+    let mut character = common::character();
+    let trigger_key = common::data("test.TriggerKey", vec![common::field("TriggerGroup", TypeIDs::S32, RSZValue::Int32(0))]);
+    character.action_list[0] = common::action(600, "5LP", vec![(common::key_data(10, 20), trigger_key)]);
+    character.action_list[0].objects[0].action.instance_infos[1].hash = TRIGGER_KEY_CLASS;
+
+    let group = common::data("test.TriggerGroup", vec![common::field("Flag", TypeIDs::UknType, RSZValue::Unk(vec![0b0000_0101, 0]))]);
+    let trigger = |action_id: i32| (TRIGGER_CLASS, common::data("test.Trigger", vec![common::field("ActionID", TypeIDs::S32, RSZValue::Int32(action_id))]));
+    character.data_id_table.extend([DataId::TriggerGroup, DataId::Trigger]);
+    character.data_list_table.push(common::data_list(vec![0], vec![(TRIGGER_GROUP_CLASS, group)]));
+    character.data_list_table.push(common::data_list(vec![0, 1, 2], vec![trigger(601), trigger(650), trigger(700)]));
+    character
+}
+
+#[test]
+fn trigger_group_bitsets_are_read_least_significant_bit_first() {
+    let graph = character_with_cancels().cancel_graph();
+    let edges: Vec<(i32, i32, u32, i32, i32)> = graph.edges.iter()
+        .map(|edge| (edge.from, edge.to, edge.trigger_id, edge.start_frame, edge.end_frame))
+        .collect();
+    assert_eq!(edges, vec![(600, 601, 0, 10, 20), (600, 700, 2, 10, 20)]);
+}
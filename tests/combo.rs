@@ -0,0 +1,71 @@
+mod common;
+
+use sf6_rsz_parser::fchar::combo::{ComboResult, ScalingRules};
+
+//5LP hits once for 300, 2MP hits for 600 and then 300
+fn combo(notation: &str) -> ComboResult {
+    common::linked_character().parse_combo(notation, &ScalingRules::sf6()).unwrap()
+}
+
+fn scaling(result: &ComboResult) -> Vec<f64> {
+    result.hits.iter().map(|hit| hit.scaling).collect()
+}
+
+#[test]
+fn hits_follow_the_scaling_table() {
+    let result = combo("2MP > 5LP");
+    assert_eq!(scaling(&result), vec![100.0, 100.0, 80.0]);
+    assert_eq!(result.damage, 600.0 + 300.0 + 240.0);
+    assert_eq!(result.stun, 200.0 + 100.0 + 80.0);
+}
+
+#[test]
+fn light_starters_scale_every_later_hit() {
+    let result = combo("5LP > 2MP");
+    assert_eq!(scaling(&result), vec![100.0, 90.0, 70.0]);
+    assert_eq!(result.damage, 300.0 + 540.0 + 210.0);
+}
+
+#[test]
+fn drive_rush_scales_the_rest_of_the_combo() {
+    assert_eq!(scaling(&combo("2MP > DR > 2MP")), vec![100.0, 100.0, 68.0, 59.0]);
+    //a drive rush opening the combo is the starter, not a cancel
+    assert_eq!(scaling(&combo("DR > 2MP > 2MP")), vec![100.0, 100.0, 80.0, 70.0]);
+}
+
+#[test]
+fn supers_keep_their_minimum_scaling() {
+    let rules = ScalingRules::sf6();
+    assert_eq!(rules.scaling(9, false, false, None), 10.0);
+    assert_eq!(rules.scaling(9, false, false, Some("SA1")), 30.0);
+    assert_eq!(rules.scaling(9, true, true, Some("SA3")), 50.0);
+    assert_eq!(rules.scaling(0, false, false, Some("CA")), 100.0);
+    assert_eq!(scaling(&combo("2MP > 2MP > 2MP[SA2] > 2MP[SA2]")), vec![100.0, 100.0, 80.0, 70.0, 60.0, 50.0, 40.0, 40.0]);
+}
+
+#[test]
+fn actions_are_resolved_by_name_or_by_tagged_id() {
+    let character = common::linked_character();
+    assert_eq!(character.resolve_combo_action("2mp").unwrap(), 601);
+    assert_eq!(character.resolve_combo_action("#600").unwrap(), 600);
+}
+
+#[test]
+fn unresolved_and_empty_combos_are_errors() {
+    let character = common::linked_character();
+    let rules = ScalingRules::sf6();
+    for notation in ["", " > ", "2", "2 > 5LP", "5HP", "#999", "#x", "2MP[SA9]"] {
+        assert!(character.parse_combo(notation, &rules).is_err(), "{} should not parse", notation);
+    }
+}
+
+#[test]
+fn rules_files_override_only_the_values_they_list() {
+    let rules = ScalingRules::from_json(r#"{"drive_rush_scaling": 20.0, "starter_scaling": 15.0}"#).unwrap();
+    assert_eq!(rules.scaling(2, true, true, None), 52.0);
+    assert_eq!(rules.hit_scaling, ScalingRules::sf6().hit_scaling);
+    let result = common::linked_character().parse_combo("2MP > DR > 2MP", &rules).unwrap();
+    assert_eq!(scaling(&result), vec![100.0, 100.0, 64.0, 56.0]);
+
+    assert!(ScalingRules::from_json(r#"{"drive_rush_scaling": "high"}"#).is_err());
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use common::{CHARGE_CLASS, COMMAND_CLASS, COMMAND_STEP_CLASS};
+use sf6_rsz_parser::fchar::command::*;
+use sf6_rsz_parser::fchar::{parse_fchar, write_fchar, CharacterAsset, DataId};
+use sf6_rsz_parser::profile::SF6;
+use sf6_rsz_parser::rsz::json_parser::TypeIDs;
+use sf6_rsz_parser::rsz::{RSZData, RSZValue};
+
+fn step(key_flags: u32, window: i32, charge_id: i32) -> (u32, RSZData) {
+    (COMMAND_STEP_CLASS, common::data("test.CommandStep", vec![
+        common::field("OkKeyFlags", TypeIDs::U32, RSZValue::UInt32(key_flags)),
+        common::field("FrameNum", TypeIDs::S32, RSZValue::Int32(window)),
+        common::field("ChargeId", TypeIDs::S32, RSZValue::Int32(charge_id)),
+    ]))
+}
+
+fn command(steps: &[i32]) -> (u32, RSZData) {
+    let inputs = steps.iter().map(|step| RSZValue::Int32(*step)).collect();
+    (COMMAND_CLASS, common::data("test.Command", vec![common::field("Inputs", TypeIDs::Object, RSZValue::List(inputs))]))
+}
+
+//Command 0 is a quarter circle forward with any punch, command 1 a back charge into forward with any kick
+fn character_with_commands() -> CharacterAsset {
+    let mut character = common::character();
+    let mut commands = common::data_list(vec![0, 1], vec![
+        step(INPUT_DOWN, 10, -1),
+        step(INPUT_DOWN | INPUT_FORWARD, 8, -1),
+        step(INPUT_FORWARD | INPUT_ANY_PUNCH, 6, -1),
+        command(&[1, 2, 3]),
+        step(INPUT_BACK, 0, 0),
+        step(INPUT_FORWARD | INPUT_ANY_KICK, 10, -1),
+        command(&[5, 6]),
+    ]);
+    commands.data_rsz.object_table = vec![4, 7];
+    let charges = common::data_list(vec![0], vec![(CHARGE_CLASS, common::data("test.Charge", vec![
+        common::field("ChargeFrame", TypeIDs::S32, RSZValue::Int32(45)),
+    ]))]);
+    character.data_id_table.extend([DataId::CommandParamSub, DataId::ChargeParamSub]);
+    character.data_list_table.extend([commands, charges]);
+    character
+}
+
+fn notations(character: &CharacterAsset) -> Vec<String> {
+    character.decode_commands().into_iter().map(|command| command.notation).collect()
+}
+
+#[test]
+fn commands_decode_to_numpad_notation() {
+    let character = character_with_commands();
+    assert_eq!(notations(&character), vec!["236P", "[4]6K"]);
+    let charge = &character.command_steps(1).unwrap()[0];
+    assert_eq!((charge.charge, charge.charge_id, charge.charge_frames), (true, Some(0), Some(45)));
+}
+
+#[test]
+fn any_strength_and_held_buttons_are_told_apart() {
+    let any_punch = parse_notation("6P").unwrap();
+    assert_eq!(any_punch[0].key_flags, INPUT_FORWARD | INPUT_ANY_PUNCH);
+    let all_punches = parse_notation("6LP+MP+HP").unwrap();
+    assert_eq!(all_punches[0].key_flags, INPUT_FORWARD | INPUT_LP | INPUT_MP | INPUT_HP);
+    assert_eq!(step_to_notation(&all_punches[0]), "6LP+MP+HP");
+    assert_eq!(step_to_notation(&any_punch[0]), "6P");
+
+    let charge = parse_notation("[4]6K").unwrap();
+    assert!(charge[0].charge);
+    assert_eq!(charge[0].charge_id, None);
+}
+
+#[test]
+fn decoded_commands_encode_back_to_the_same_file() {
+    common::load_schema();
+    let mut character = character_with_commands();
+    let written = write_fchar(&character, &SF6);
+    for decoded in character.decode_commands() {
+        character.encode_command(decoded.id, &decoded.step_notation).unwrap();
+    }
+    assert_eq!(write_fchar(&character, &SF6), written);
+}
+
+#[test]
+fn encoded_commands_keep_their_windows_and_charges() {
+    common::load_schema();
+    let mut character = character_with_commands();
+    character.encode_command(0, "214K").unwrap();
+    character.encode_command(1, "[1]3LP+LK").unwrap();
+
+    let (_, parsed) = parse_fchar(&write_fchar(&character, &SF6), &SF6).unwrap();
+    assert_eq!(notations(&parsed), vec!["214K", "[1]3LP+LK"]);
+    let windows: Vec<i32> = parsed.command_steps(0).unwrap().iter().map(|step| step.window).collect();
+    assert_eq!(windows, vec![10, 8, 6]);
+    assert_eq!(parsed.command_steps(1).unwrap()[0].charge_frames, Some(45));
+
+    assert!(character.encode_command(0, "[2]36P").is_err());
+    assert!(character.encode_command(0, "6P").is_err());
+    assert!(character.encode_command(2, "6P").is_err());
+}
@@ -0,0 +1,246 @@
+#![allow(dead_code)]
+
+use sf6_rsz_parser::detect::RSZ_MAGIC;
+use sf6_rsz_parser::fchar::{ActionData, ActionList, ActionListInfo, ActionListTable, CharacterAsset, CharacterAssetHeader, DataId, DataListInfo, DataListItem, KeyData, Object, ObjectData, ObjectInfo, StringInfo, StyleData};
+use sf6_rsz_parser::rsz::json_parser::{parse_json, TypeIDs};
+use sf6_rsz_parser::rsz::{InstanceInfo, RSZData, RSZField, RSZHeader, RSZValue, RSZ};
+
+pub const ACTION_CLASS: u32 = 0x10;
+pub const KEY_CLASS: u32 = 0x11;
+pub const ATTACK_CLASS: u32 = 0x12;
+pub const PERSONAL_CLASS: u32 = 0x13;
+pub const STYLE_CLASS: u32 = 0x14;
+pub const KEY_GROUP_CLASS: u32 = 0x15;
+pub const COMMAND_CLASS: u32 = 0x16;
+pub const COMMAND_STEP_CLASS: u32 = 0x17;
+pub const CHARGE_CLASS: u32 = 0x18;
+pub const TARGET_CLASS: u32 = 0x19;
+pub const LINKER_CLASS: u32 = 0x1a;
+pub const FIXED_CLASS: u32 = 0x1b;
+
+//A tiny schema standing in for a game dump, every test binary loads the same one
+pub const SCHEMA: &str = r#"{
+    "10": {"name": "test.ActionInfo", "fields": [
+        {"name": "ActionID", "type": "S32", "size": 4, "align": 4, "array": false},
+        {"name": "Name", "type": "String", "size": 4, "align": 4, "array": false}
+    ]},
+    "11": {"name": "test.AttackKey", "fields": [
+        {"name": "AttackDataListIndex", "type": "S32", "size": 4, "align": 4, "array": false}
+    ]},
+    "12": {"name": "test.AttackData", "fields": [
+        {"name": "DamageValue", "type": "S32", "size": 4, "align": 4, "array": false},
+        {"name": "PiyoValue", "type": "S32", "size": 4, "align": 4, "array": false}
+    ]},
+    "13": {"name": "test.PersonalData", "fields": [
+        {"name": "Vital", "type": "S32", "size": 4, "align": 4, "array": false}
+    ]},
+    "14": {"name": "test.StyleData", "fields": [
+        {"name": "Gravity", "type": "F32", "size": 4, "align": 4, "array": false},
+        {"name": "JumpFrame", "type": "S32", "size": 4, "align": 4, "array": false}
+    ]},
+    "15": {"name": "test.KeyGroup", "fields": [
+        {"name": "Hit", "type": "Object", "size": 4, "align": 4, "array": false}
+    ]},
+    "16": {"name": "test.Command", "fields": [
+        {"name": "Inputs", "type": "Object", "size": 4, "align": 4, "array": true}
+    ]},
+    "17": {"name": "test.CommandStep", "fields": [
+        {"name": "OkKeyFlags", "type": "U32", "size": 4, "align": 4, "array": false},
+        {"name": "FrameNum", "type": "S32", "size": 4, "align": 4, "array": false},
+        {"name": "ChargeId", "type": "S32", "size": 4, "align": 4, "array": false}
+    ]},
+    "18": {"name": "test.Charge", "fields": [
+        {"name": "ChargeFrame", "type": "S32", "size": 4, "align": 4, "array": false}
+    ]},
+    "19": {"name": "test.Target", "fields": [
+        {"name": "Hp", "type": "S32", "size": 4, "align": 4, "array": false}
+    ]},
+    "1a": {"name": "test.Linker", "fields": [
+        {"name": "Hp", "type": "S32", "size": 4, "align": 4, "array": false},
+        {"name": "Target", "type": "GameObjectRef", "size": 16, "align": 8, "array": false}
+    ]},
+    "1b": {"name": "test.Fixed", "fields": [
+        {"name": "Offset", "type": "Sfix", "size": 4, "align": 4, "array": false},
+        {"name": "Position", "type": "Sfix3", "size": 12, "align": 4, "array": false}
+    ]}
+}"#;
+
+pub fn load_schema() {
+    parse_json(SCHEMA.as_bytes().to_vec()).unwrap();
+}
+
+pub fn field(name: &str, value_type: TypeIDs, value: RSZValue) -> RSZField {
+    RSZField {
+        name: name.to_string(),
+        value_type,
+        value,
+        alignment: 4,
+    }
+}
+
+pub fn data(name: &str, fields: Vec<RSZField>) -> RSZData {
+    RSZData {
+        name: name.to_string(),
+        fields,
+    }
+}
+
+pub fn action_info(action_id: i32, name: &str) -> RSZData {
+    data("test.ActionInfo", vec![
+        field("ActionID", TypeIDs::S32, RSZValue::Int32(action_id)),
+        field("Name", TypeIDs::String, RSZValue::String(name.to_string())),
+    ])
+}
+
+pub fn attack_key(attack_id: i32) -> RSZData {
+    data("test.AttackKey", vec![field("AttackDataListIndex", TypeIDs::S32, RSZValue::Int32(attack_id))])
+}
+
+pub fn attack_data(damage: i32, stun: i32) -> RSZData {
+    data("test.AttackData", vec![
+        field("DamageValue", TypeIDs::S32, RSZValue::Int32(damage)),
+        field("PiyoValue", TypeIDs::S32, RSZValue::Int32(stun)),
+    ])
+}
+
+pub fn style_data(gravity: f32, jump_frame: i32) -> RSZData {
+    data("test.StyleData", vec![
+        field("Gravity", TypeIDs::F32, RSZValue::Float(gravity)),
+        field("JumpFrame", TypeIDs::S32, RSZValue::Int32(jump_frame)),
+    ])
+}
+
+//A key whose attack key is a child instance, the child is stored before the key like the game does
+pub fn key_group(hit: i32) -> RSZData {
+    data("test.KeyGroup", vec![field("Hit", TypeIDs::Object, RSZValue::Int32(hit))])
+}
+
+//Every instance is a root instance, in order, after the null instance
+pub fn rsz(instances: Vec<(u32, RSZData)>) -> RSZ {
+    let mut instance_infos = vec![InstanceInfo { hash: 0, crc: 0 }];
+    instance_infos.extend(instances.iter().map(|(hash, _)| InstanceInfo { hash: *hash, crc: 0 }));
+    RSZ {
+        header: RSZHeader {
+            magic: RSZ_MAGIC,
+            version: 16,
+            ..Default::default()
+        },
+        object_table: (1..=instances.len() as i32).collect(),
+        instance_infos,
+        userdata_infos: vec![],
+        data: instances.into_iter().map(|(_, data)| data).collect(),
+    }
+}
+
+//One object per key, the object's RSZ holds the key's instance
+pub fn action(action_id: i32, name: &str, keys: Vec<(KeyData, RSZData)>) -> ActionList {
+    let objects = keys.into_iter().map(|(key_data, key)| Object {
+        info: ObjectInfo {
+            object_offset: 0,
+            data_start_offset: 0,
+            rsz_offset: 0,
+            rsz_end: 0,
+            object_data: ObjectData {
+                data_count: 1,
+                reserved: 0,
+                key_data: vec![key_data],
+            },
+        },
+        action: rsz(vec![(KEY_CLASS, key)]),
+    }).collect::<Vec<Object>>();
+    ActionList {
+        info: ActionListInfo {
+            action_offset: 0,
+            data_start_offset: 0,
+            rsz_offset: 0,
+            rsz_end: 0,
+            action_count: 1,
+            object_count: objects.len() as u32,
+            action_data: ActionData {
+                action_id,
+                frames: 30,
+                key_start_frame: 0,
+                key_end_frame: 30,
+            },
+        },
+        action: rsz(vec![(ACTION_CLASS, action_info(action_id, name))]),
+        objects,
+    }
+}
+
+pub fn key_data(start: i32, end: i32) -> KeyData {
+    KeyData {
+        key_start_frame: start,
+        key_end_frame: end,
+    }
+}
+
+pub fn data_list(ids: Vec<u32>, instances: Vec<(u32, RSZData)>) -> DataListItem {
+    DataListItem {
+        data_list_offset: 0,
+        info: DataListInfo {
+            data_start_offset: 0,
+            rsz_offset: 0,
+            data_end_offset: 0,
+            data_count: ids.len() as u32,
+        },
+        data_ids: ids,
+        data_rsz: rsz(instances),
+    }
+}
+
+pub fn string_info(string: &str) -> StringInfo {
+    StringInfo {
+        string_offset: 0,
+        string: string.to_string(),
+        action_ids: vec![],
+    }
+}
+
+//Two styles where the second inherits from the first, two actions hitting with the two attack data entries
+pub fn character() -> CharacterAsset {
+    CharacterAsset {
+        header: CharacterAssetHeader {
+            version: 17,
+            magic: u32::from_le_bytes(*b"CHAR"),
+            id_table_offset: 0,
+            parent_id_table_offset: 0,
+            action_list_table_offset: 0,
+            data_id_table_offset: 0,
+            data_list_table_offset: 0,
+            string_object_offset: 0,
+            string_offset: 0,
+            object_table_rsz_offset: 0,
+            object_table_rsz_end: 0,
+            object_count: 0,
+            style_count: 2,
+            data_count: 1,
+            string_count: 2,
+        },
+        id_table: vec![0, 1],
+        parent_id_table: vec![-1, 0],
+        action_list_table: ActionListTable::default(),
+        default_style_data: rsz(vec![(STYLE_CLASS, style_data(0.5, 40))]),
+        style_data: vec![StyleData {
+            data_start_offset: 0,
+            rsz_offset: 0,
+            data_end_offset: 0,
+            rsz: rsz(vec![(STYLE_CLASS, style_data(0.75, 40))]),
+        }],
+        action_list: vec![
+            action(600, "5LP", vec![(key_data(4, 6), attack_key(0))]),
+            action(601, "2MP", vec![(key_data(6, 8), attack_key(1)), (key_data(12, 14), attack_key(0))]),
+        ],
+        data_id_table: vec![DataId::AttackDataParams],
+        data_list_table: vec![data_list(vec![0, 1], vec![(ATTACK_CLASS, attack_data(300, 100)), (ATTACK_CLASS, attack_data(600, 200))])],
+        personal_data: rsz(vec![(PERSONAL_CLASS, data("test.PersonalData", vec![field("Vital", TypeIDs::S32, RSZValue::Int32(10000))]))]),
+        string_table: vec![string_info("5LP"), string_info("2MP")],
+    }
+}
+
+//The character written out and parsed back, which links its string table to the actions
+pub fn linked_character() -> CharacterAsset {
+    load_schema();
+    let written = sf6_rsz_parser::fchar::write_fchar(&character(), &sf6_rsz_parser::profile::SF6);
+    sf6_rsz_parser::fchar::parse_fchar(&written, &sf6_rsz_parser::profile::SF6).unwrap().1
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use sf6_rsz_parser::fchar::curve::curves_to_csv;
+use sf6_rsz_parser::fchar::{CharacterAsset, DataId};
+use sf6_rsz_parser::rsz::json_parser::TypeIDs;
+use sf6_rsz_parser::rsz::{Float3, RSZData, RSZValue};
+
+const CURVE_CLASS: u32 = 0x30;
+const CURVE_KEY_CLASS: u32 = 0x31;
+
+fn curve_key(frame: i32, x: f32, y: f32, z: f32) -> (u32, RSZData) {
+    (CURVE_KEY_CLASS, common::data("test.CurveKey", vec![
+        common::field("Frame", TypeIDs::S32, RSZValue::Int32(frame)),
+        common::field("Position", TypeIDs::Vec3, RSZValue::Float3(Float3 { x, y, z })),
+    ]))
+}
+
+//Owner curve 0 has two child keys, its length sits in its own field rather than the keys' frame field
+fn character_with_curve(keys_type: TypeIDs) -> CharacterAsset {
+    let mut character = common::character();
+    let curve = common::data("test.Curve", vec![
+        common::field("Keys", keys_type, RSZValue::List(vec![RSZValue::Int32(1), RSZValue::Int32(2)])),
+        common::field("Length", TypeIDs::S32, RSZValue::Int32(6)),
+    ]);
+    let mut curves = common::data_list(vec![0], vec![curve_key(0, 0.0, 0.0, 0.0), curve_key(4, 4.0, 2.0, 1.0), (CURVE_CLASS, curve)]);
+    curves.data_rsz.object_table = vec![3];
+    character.data_id_table.push(DataId::AttackOwnerCurve);
+    character.data_list_table.push(curves);
+    character
+}
+
+#[test]
+fn object_keys_are_read_from_their_child_instances() {
+    let character = character_with_curve(TypeIDs::Object);
+    let curve = character.sampled_curve(DataId::AttackOwnerCurve, 0).unwrap();
+    assert_eq!(curve.keys.iter().map(|key| key.frame).collect::<Vec<i32>>(), vec![0, 4]);
+    assert_eq!(curve.positions.len(), 7);
+    assert_eq!(curve.positions[2], vec![2.0, 1.0, 0.5]);
+    assert_eq!(curve.positions[6], vec![4.0, 2.0, 1.0]);
+
+    let csv = curves_to_csv(&[curve]);
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("table,id,frame,x,y,z"));
+    assert_eq!(lines.nth(2), Some("AttackOwnerCurve,0,2,2,1,0.5"));
+}
+
+#[test]
+fn int_lists_are_not_read_as_child_instances() {
+    let character = character_with_curve(TypeIDs::S32);
+    let curve = character.sampled_curve(DataId::AttackOwnerCurve, 0).unwrap();
+    assert_eq!(curve.keys.iter().map(|key| key.position.clone()).collect::<Vec<Vec<f64>>>(), vec![vec![1.0], vec![2.0]]);
+}
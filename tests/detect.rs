@@ -0,0 +1,62 @@
+use sf6_rsz_parser::detect::{detect, FileKind};
+use sf6_rsz_parser::profile::Game;
+use sf6_rsz_parser::rsz::version::{DMC5_VERSION, SF6_VERSION};
+
+//A header of `header_size` bytes whose data offset at `data_offset_at` points at an RSZ magic and version
+fn header_with_rsz(magic: &[u8; 4], header_size: usize, data_offset_at: usize, rsz_version: u32) -> Vec<u8> {
+    let mut bytes: Vec<u8> = vec![0; header_size];
+    bytes[0..4].copy_from_slice(magic);
+    bytes[data_offset_at..data_offset_at + 8].copy_from_slice(&(header_size as u64).to_le_bytes());
+    bytes.extend_from_slice(b"RSZ\0");
+    bytes.extend_from_slice(&rsz_version.to_le_bytes());
+    bytes
+}
+
+#[test]
+fn user_files_take_the_game_from_the_rsz_version() {
+    let dmc5 = header_with_rsz(b"USR\0", 48, 32, DMC5_VERSION);
+    let file_type = detect(&dmc5, "em0000.user.2").unwrap();
+    assert_eq!(file_type.kind, FileKind::UserData);
+    assert_eq!(file_type.rsz_version, Some(DMC5_VERSION));
+    assert_eq!(file_type.profile().game, Game::DMC5);
+
+    let sf6 = header_with_rsz(b"USR\0", 48, 32, SF6_VERSION);
+    assert_eq!(detect(&sf6, "esf001.user.2").unwrap().profile().game, Game::SF6);
+}
+
+#[test]
+fn scene_version_comes_from_the_rsz_block_before_the_file_name() {
+    let dmc5 = header_with_rsz(b"SCN\0", 64, 56, DMC5_VERSION);
+    //a renamed file still resolves to the version of the game that wrote it
+    let file_type = detect(&dmc5, "stage.scn.20").unwrap();
+    assert_eq!(file_type.kind, FileKind::Scene);
+    assert_eq!(file_type.version, 19);
+    assert_eq!(file_type.profile().game, Game::DMC5);
+}
+
+#[test]
+fn fchar_version_is_read_from_the_header() {
+    let mut bytes: Vec<u8> = vec![];
+    bytes.extend_from_slice(&17u32.to_le_bytes());
+    bytes.extend_from_slice(b"CHAR");
+    let file_type = detect(&bytes, "renamed.bin").unwrap();
+    assert_eq!(file_type.kind, FileKind::CharacterAsset);
+    assert_eq!(file_type.version, 17);
+}
+
+#[test]
+fn bare_rsz_blocks_are_detected() {
+    let mut bytes: Vec<u8> = b"RSZ\0".to_vec();
+    bytes.extend_from_slice(&DMC5_VERSION.to_le_bytes());
+    let file_type = detect(&bytes, "block.bin").unwrap();
+    assert_eq!(file_type.kind, FileKind::Rsz);
+    assert_eq!(file_type.profile().game, Game::DMC5);
+}
+
+#[test]
+fn unknown_headers_fall_back_to_the_extension() {
+    let file_type = detect(&[0; 16], "esf001.user.2").unwrap();
+    assert_eq!(file_type.kind, FileKind::UserData);
+    assert_eq!(file_type.version, 2);
+    assert_eq!(file_type.rsz_version, None);
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use sf6_rsz_parser::fchar::CharacterAsset;
+use sf6_rsz_parser::rsz::json_parser::TypeIDs;
+use sf6_rsz_parser::rsz::RSZValue;
+
+//A second action list entry reusing id 600, with a key of its own and a different frame count
+fn character_with_repeated_id() -> CharacterAsset {
+    let mut character = common::character();
+    let camera_key = common::data("test.CameraKey", vec![common::field("CameraDataIndex", TypeIDs::S32, RSZValue::Int32(0))]);
+    let mut repeated = common::action(600, "5LP", vec![(common::key_data(2, 3), camera_key)]);
+    repeated.info.action_data.frames = 40;
+    character.action_list.push(repeated);
+    character
+}
+
+#[test]
+fn repeated_action_ids_are_paired_by_occurrence() {
+    let old = character_with_repeated_id();
+    let mut new = character_with_repeated_id();
+    new.action_list[2].info.action_data.frames = 45;
+
+    let diff = old.patch_diff(&new);
+    assert!(diff.added_actions.is_empty());
+    assert!(diff.removed_actions.is_empty());
+    assert_eq!(diff.changed_actions.len(), 1);
+    assert_eq!(diff.changed_actions[0].action_id, 600);
+    let frames = &diff.changed_actions[0].frames[0];
+    assert_eq!((frames.field, frames.old, frames.new), ("frames", 40, 45));
+
+    new.action_list.remove(2);
+    let diff = old.patch_diff(&new);
+    assert_eq!(diff.removed_actions, vec![600]);
+    assert!(diff.changed_actions.is_empty());
+}
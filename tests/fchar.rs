@@ -0,0 +1,120 @@
+mod common;
+
+use sf6_rsz_parser::fchar::{missing_schema_fields, parse_fchar, write_fchar, DataId};
+use sf6_rsz_parser::profile::SF6;
+
+#[test]
+fn written_fchar_parses_back_and_writes_the_same_bytes() {
+    common::load_schema();
+    let written = write_fchar(&common::character(), &SF6);
+    let (_, parsed) = parse_fchar(&written, &SF6).unwrap();
+    assert_eq!(parsed.header.style_count, 2);
+    assert_eq!(parsed.parent_id_table, vec![-1, 0]);
+    assert_eq!(parsed.default_style_data.data[0].get_f64("Gravity"), Some(0.5));
+    assert_eq!(parsed.style_data[0].rsz.data[0].get_f64("Gravity"), Some(0.75));
+
+    let action_ids: Vec<i32> = parsed.action_list.iter().map(|action| action.info.action_data.action_id).collect();
+    assert_eq!(action_ids, vec![600, 601]);
+    let hits = &parsed.action(601).unwrap().objects;
+    assert_eq!(hits.len(), 2);
+    assert_eq!(hits[1].info.object_data.key_data[0].key_start_frame, 12);
+    assert_eq!(hits[1].action.data[0].get_i64("AttackDataListIndex"), Some(0));
+
+    let attack_table = parsed.data_table(DataId::AttackDataParams).unwrap();
+    assert_eq!(attack_table.entry(1).unwrap().get_i64("DamageValue"), Some(600));
+    assert_eq!(parsed.personal_data.data[0].get_i64("Vital"), Some(10000));
+
+    assert_eq!(write_fchar(&parsed, &SF6), written);
+}
+
+#[test]
+fn string_table_is_linked_to_the_actions_using_each_string() {
+    common::load_schema();
+    let mut character = common::character();
+    character.string_table.push(common::string_info("unused"));
+    let written = write_fchar(&character, &SF6);
+    let (_, parsed) = parse_fchar(&written, &SF6).unwrap();
+    let strings: Vec<(&str, &[i32])> = parsed.string_table.iter()
+        .map(|string_info| (string_info.string.as_str(), &string_info.action_ids[..]))
+        .collect();
+    assert_eq!(strings, vec![("5LP", &[600][..]), ("2MP", &[601][..]), ("unused", &[][..])]);
+}
+
+#[test]
+fn fchar_versions_outside_the_profile_are_rejected() {
+    common::load_schema();
+    let mut character = common::character();
+    character.header.version = 16;
+    let written = write_fchar(&character, &SF6);
+    assert!(parse_fchar(&written, &SF6).is_err());
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> usize {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap()) as usize
+}
+
+fn put_u64(bytes: &mut [u8], offset: usize, value: u64) {
+    bytes[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+}
+
+#[test]
+fn out_of_range_style_offsets_are_errors() {
+    common::load_schema();
+    let written = write_fchar(&common::character(), &SF6);
+    //the 96 byte header and two style id tables are followed by the style offsets
+    let style_table = 112;
+    let action_list_table = read_u64(&written, style_table);
+    let style = read_u64(&written, style_table + 8);
+    let past_end = written.len() as u64 + 16;
+    for offset in [style_table, action_list_table + 8, style_table + 8, style + 8] {
+        let mut corrupted = written.clone();
+        put_u64(&mut corrupted, offset, past_end);
+        assert!(parse_fchar(&corrupted, &SF6).is_err(), "offset at {:#x} was not rejected", offset);
+    }
+}
+
+#[test]
+fn field_names_missing_from_the_schema_are_listed_once() {
+    common::load_schema();
+    let missing = missing_schema_fields();
+    for name in ["DamageValue", "PiyoValue", "Vital", "Gravity", "OkKeyFlags", "ActionID", "Position"] {
+        assert!(!missing.contains(&name), "{} is in the schema", name);
+    }
+    assert!(missing.contains(&"FrontWalkSpeed"));
+    assert_eq!(missing.iter().filter(|name| **name == "Frame").count(), 1);
+}
+
+#[test]
+fn broken_string_tables_are_rejected() {
+    common::load_schema();
+    let written = write_fchar(&common::character(), &SF6);
+    let (_, parsed) = parse_fchar(&written, &SF6).unwrap();
+    let table = parsed.header.string_object_offset as usize;
+
+    let mut bad_offset = written.clone();
+    bad_offset[table..table + 8].copy_from_slice(&(written.len() as u64 + 2).to_le_bytes());
+    assert!(parse_fchar(&bad_offset, &SF6).is_err());
+
+    //the string count sits at the end of the header, more entries than the file holds run past its end
+    let mut truncated = written.clone();
+    truncated[92..96].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(parse_fchar(&truncated, &SF6).is_err());
+}
+
+#[test]
+fn truncated_headers_are_errors() {
+    common::load_schema();
+    let written = write_fchar(&common::character(), &SF6);
+    for length in [0, 40, 95] {
+        assert!(parse_fchar(&written[..length], &SF6).is_err());
+    }
+}
+
+#[test]
+fn cut_off_files_return_errors_instead_of_panicking() {
+    common::load_schema();
+    let written = write_fchar(&common::character(), &SF6);
+    for length in 0..written.len() {
+        assert!(parse_fchar(&written[..length], &SF6).is_err(), "a file cut at {:#x} parsed", length);
+    }
+}
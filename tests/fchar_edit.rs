@@ -0,0 +1,110 @@
+mod common;
+
+use common::{KEY_CLASS, KEY_GROUP_CLASS};
+use sf6_rsz_parser::fchar::{parse_fchar, write_fchar, CharacterAsset};
+use sf6_rsz_parser::profile::SF6;
+
+//Action 601 with a single object holding two keys, each key pointing at its own attack key instance
+fn character_with_key_groups() -> CharacterAsset {
+    let mut character = common::character();
+    let action = character.action_list.iter_mut().find(|action| action.info.action_data.action_id == 601).unwrap();
+    let object = &mut action.objects[0];
+    object.action = common::rsz(vec![
+        (KEY_CLASS, common::attack_key(0)),
+        (KEY_GROUP_CLASS, common::key_group(1)),
+        (KEY_CLASS, common::attack_key(1)),
+        (KEY_GROUP_CLASS, common::key_group(3)),
+    ]);
+    object.action.object_table = vec![2, 4];
+    object.info.object_data.key_data = vec![common::key_data(6, 8), common::key_data(12, 14)];
+    object.info.object_data.data_count = 2;
+    character
+}
+
+fn hit_of_key(character: &CharacterAsset, key_index: usize) -> (i64, Option<i64>) {
+    let object = &character.action(601).unwrap().objects[0];
+    let key = object.action.instance_data(object.action.object_table[key_index]).unwrap();
+    let hit = key.get_i64("Hit").unwrap();
+    let attack = object.action.instance_data(hit as i32).and_then(|attack_key| attack_key.get_i64("AttackDataListIndex"));
+    (hit, attack)
+}
+
+#[test]
+fn cloned_action_carries_the_new_id_in_its_rsz_and_string_table() {
+    let mut character = common::linked_character();
+    character.clone_action(601, 700).unwrap();
+
+    let clone = character.action(700).unwrap();
+    assert_eq!(clone.action.data[0].get_i64("ActionID"), Some(700));
+    assert_eq!(character.action(601).unwrap().action.data[0].get_i64("ActionID"), Some(601));
+    assert_eq!(character.string_table[1].action_ids, vec![601, 700]);
+    assert_eq!(character.action_list_table.action_list_count, 3);
+
+    let (_, parsed) = parse_fchar(&write_fchar(&character, &SF6), &SF6).unwrap();
+    assert_eq!(parsed.action(700).unwrap().objects.len(), 2);
+    assert_eq!(parsed.string_table[1].action_ids, vec![601, 700]);
+
+    character.remove_action(700).unwrap();
+    assert_eq!(character.string_table[1].action_ids, vec![601]);
+    assert!(character.action(700).is_none());
+}
+
+#[test]
+fn added_key_gets_its_own_copy_of_the_template_children() {
+    common::load_schema();
+    let mut character = character_with_key_groups();
+    let key_index = character.add_key(601, 0, 0, common::key_data(2, 40)).unwrap();
+    assert_eq!(key_index, 2);
+
+    let (template_hit, _) = hit_of_key(&character, 0);
+    let (clone_hit, clone_attack) = hit_of_key(&character, 2);
+    assert_ne!(clone_hit, template_hit);
+    assert_eq!(clone_attack, Some(0));
+
+    let action = character.action_mut(601).unwrap();
+    action.objects[0].action.instance_data_mut(clone_hit as i32).unwrap().set_i64("AttackDataListIndex", 1);
+    assert_eq!(hit_of_key(&character, 0).1, Some(0));
+
+    let action = character.action(601).unwrap();
+    assert_eq!(action.objects[0].action.header.instance_count, 7);
+    assert_eq!(action.objects[0].info.object_data.data_count, 3);
+    assert_eq!(action.info.action_count, 1);
+    assert_eq!((action.info.action_data.key_start_frame, action.info.action_data.key_end_frame), (2, 40));
+
+    let (_, parsed) = parse_fchar(&write_fchar(&character, &SF6), &SF6).unwrap();
+    assert_eq!(hit_of_key(&parsed, 2), (clone_hit, Some(1)));
+}
+
+#[test]
+fn removed_key_takes_its_children_and_the_rest_are_renumbered() {
+    common::load_schema();
+    let mut character = character_with_key_groups();
+    let removed = character.remove_key(601, 0, 0).unwrap();
+    assert_eq!(removed.key_start_frame, 6);
+
+    let object = &character.action(601).unwrap().objects[0];
+    assert_eq!(object.action.object_table, vec![2]);
+    assert_eq!(object.action.data.len(), 2);
+    assert_eq!(object.action.instance_infos.len(), 3);
+    assert_eq!(object.action.instance_infos[1].hash, KEY_CLASS);
+    assert_eq!(object.action.header.instance_count, 3);
+    assert_eq!(hit_of_key(&character, 0), (1, Some(1)));
+    assert_eq!(object.info.object_data.data_count, 1);
+
+    let (_, parsed) = parse_fchar(&write_fchar(&character, &SF6), &SF6).unwrap();
+    assert_eq!(hit_of_key(&parsed, 0), (1, Some(1)));
+}
+
+#[test]
+fn children_shared_with_another_key_are_kept() {
+    common::load_schema();
+    let mut character = character_with_key_groups();
+    let object = &mut character.action_mut(601).unwrap().objects[0];
+    object.action.instance_data_mut(4).unwrap().set_i64("Hit", 1);
+    character.remove_key(601, 0, 1).unwrap();
+
+    let object = &character.action(601).unwrap().objects[0];
+    assert_eq!(object.action.object_table, vec![2]);
+    assert_eq!(object.action.data.len(), 3);
+    assert_eq!(hit_of_key(&character, 0), (1, Some(0)));
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use sf6_rsz_parser::fchar::personal::{compare_personal_data, folder_profile};
+use sf6_rsz_parser::fchar::write_fchar;
+use sf6_rsz_parser::profile::{Game, SF6};
+
+#[test]
+fn folders_are_compared_by_header_and_broken_files_are_skipped() {
+    common::load_schema();
+    let dir = std::env::temp_dir().join(format!("sf6_rsz_parser_personal_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let written = write_fchar(&common::character(), &SF6);
+    std::fs::write(dir.join("000.fchar.17"), &written).unwrap();
+    //renamed files are still found by their magic
+    std::fs::write(dir.join("renamed.bin"), &written).unwrap();
+    //a cut off file makes the parser fail partway through
+    std::fs::write(dir.join("001.fchar.17"), &written[..200]).unwrap();
+    std::fs::write(dir.join("notes.txt"), b"not an fchar").unwrap();
+
+    let comparison = compare_personal_data(&dir, &SF6).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let names: Vec<&str> = comparison.characters.iter().map(|character| character.file_name.as_str()).collect();
+    assert_eq!(names, vec!["000.fchar.17", "renamed.bin"]);
+    assert_eq!(comparison.characters[0].parameters.health, Some(10000.0));
+    let skipped: Vec<&str> = comparison.skipped.iter().map(|skipped| skipped.file_name.as_str()).collect();
+    assert_eq!(skipped, vec!["001.fchar.17"]);
+}
+
+#[test]
+fn folders_take_the_profile_of_their_first_fchar_file() {
+    let dir = std::env::temp_dir().join(format!("sf6_rsz_parser_folder_profile_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("notes.txt"), b"not an fchar").unwrap();
+    let no_fchar = folder_profile(&dir).unwrap();
+    std::fs::write(dir.join("renamed.bin"), write_fchar(&common::character(), &SF6)).unwrap();
+    let profile = folder_profile(&dir).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(no_fchar.is_none());
+    assert_eq!(profile.map(|profile| profile.game), Some(Game::SF6));
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::{LINKER_CLASS, TARGET_CLASS};
+use sf6_rsz_parser::detect::PFB_MAGIC;
+use sf6_rsz_parser::prefab::{parse_prefab, write_prefab, Prefab, PrefabHeader};
+use sf6_rsz_parser::profile::{GameProfile, DMC5, SF6};
+use sf6_rsz_parser::rsz::json_parser::TypeIDs;
+use sf6_rsz_parser::rsz::{GameObjectInfo, GameObjectRefInfo, InstanceInfo, ResourceInfo, RSZField, RSZValue, GUID};
+
+fn target(hp: i32) -> (u32, sf6_rsz_parser::rsz::RSZData) {
+    (TARGET_CLASS, common::data("test.Target", vec![common::field("Hp", TypeIDs::S32, RSZValue::Int32(hp))]))
+}
+
+fn gameobject_info(id: i32, parent_id: i32) -> GameObjectInfo {
+    GameObjectInfo {
+        id,
+        parent_id,
+        component_count: 0,
+    }
+}
+
+//GameObject 1 points its Target field at GameObject 0
+fn linked_prefab() -> Prefab {
+    let guid: GUID = serde_json::from_str(&format!("{{\"uuid\": {:?}}}", [7u8; 16])).unwrap();
+    let linker = common::data("test.Linker", vec![
+        common::field("Hp", TypeIDs::S32, RSZValue::Int32(200)),
+        RSZField {
+            name: "Target".to_string(),
+            value_type: TypeIDs::GameObjectRef,
+            value: RSZValue::GUID(guid),
+            alignment: 8,
+        },
+    ]);
+    Prefab {
+        header: PrefabHeader {
+            magic: PFB_MAGIC,
+            info_count: 2,
+            resource_count: 1,
+            gameobject_ref_info_count: 1,
+            userdata_count: Some(0),
+            reserved: Some(0),
+            gameobject_ref_info_tbl: 0,
+            resource_info_tbl: 0,
+            userdata_info_tbl: Some(0),
+            data_offset: 0,
+        },
+        gameobject_infos: vec![gameobject_info(0, -1), gameobject_info(1, 0)],
+        userdata_infos: vec![],
+        gameobject_ref_infos: vec![GameObjectRefInfo {
+            object_id: 1,
+            property_id: 1,
+            array_index: -1,
+            target_id: 0,
+        }],
+        resource_infos: vec![ResourceInfo {
+            str_offset: None,
+            string: "test/target.mesh".to_string(),
+        }],
+        gameobject: common::rsz(vec![target(100), (LINKER_CLASS, linker)]),
+    }
+}
+
+fn round_trip(prefab: &Prefab, profile: &GameProfile) -> Prefab {
+    let written = write_prefab(prefab, profile);
+    let (_, parsed) = parse_prefab(&written, profile).unwrap();
+    assert_eq!(write_prefab(&parsed, profile), written);
+    parsed
+}
+
+#[test]
+fn prefabs_survive_a_round_trip() {
+    common::load_schema();
+    for profile in [&SF6, &DMC5] {
+        let parsed = round_trip(&linked_prefab(), profile);
+        assert_eq!(parsed.resource_infos[0].string, "test/target.mesh");
+        assert_eq!(parsed.gameobject_infos.len(), 2);
+        assert!(parsed.gameobject_links() == linked_prefab().gameobject_links());
+    }
+}
+
+#[test]
+fn written_links_follow_their_objects() {
+    common::load_schema();
+    let mut prefab = linked_prefab();
+    let links = prefab.gameobject_links();
+    assert_eq!((links[0].source_instance, links[0].field.as_str(), links[0].target_instance), (2, "Target", 1));
+
+    //a new GameObject at the front of the object table moves both linked objects
+    prefab.gameobject.instance_infos.push(InstanceInfo { hash: TARGET_CLASS, crc: 0 });
+    prefab.gameobject.data.push(target(300).1);
+    prefab.gameobject.object_table.insert(0, 3);
+    prefab.gameobject_infos = vec![gameobject_info(0, -1), gameobject_info(1, -1), gameobject_info(2, 1)];
+    prefab.sync_gameobject_ref_infos(&links);
+    let ref_info = &prefab.gameobject_ref_infos[0];
+    assert_eq!((ref_info.object_id, ref_info.property_id, ref_info.target_id), (2, 1, 1));
+
+    let parsed = round_trip(&prefab, &SF6);
+    assert!(parsed.gameobject_links() == links);
+    let resolved = parsed.resolved_links();
+    assert_eq!(resolved[0].target.unwrap().get_i64("Hp"), Some(100));
+
+    //links to a GameObject that is gone are dropped
+    prefab.gameobject.object_table.remove(1);
+    prefab.gameobject_infos = vec![gameobject_info(0, -1), gameobject_info(1, -1)];
+    prefab.sync_gameobject_ref_infos(&links);
+    assert!(prefab.gameobject_ref_infos.is_empty());
+    assert_eq!(round_trip(&prefab, &SF6).header.gameobject_ref_info_count, 0);
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use sf6_rsz_parser::detect::{detect, FileKind};
+use sf6_rsz_parser::fchar::{parse_fchar, write_fchar};
+use sf6_rsz_parser::profile::{Game, DMC5, PROFILES, SF6};
+
+#[test]
+fn profiles_pair_each_file_kind_with_its_version() {
+    let versions: Vec<(Game, Vec<Option<u32>>)> = PROFILES.iter().map(|profile| (profile.game, vec![
+        profile.file_version(FileKind::CharacterAsset),
+        profile.file_version(FileKind::Prefab),
+        profile.file_version(FileKind::Scene),
+        profile.file_version(FileKind::UserData),
+        profile.file_version(FileKind::Rsz),
+    ])).collect();
+    assert_eq!(versions, vec![
+        (Game::SF6, vec![Some(17), Some(17), Some(20), Some(2), Some(16)]),
+        (Game::DMC5, vec![None, Some(16), Some(19), Some(2), Some(8)]),
+    ]);
+}
+
+#[test]
+fn extensions_pick_the_profile_of_their_version() {
+    //no header to go by, so only the file name tells the game
+    let files = [
+        ("pl0000.fchar.17", FileKind::CharacterAsset, Game::SF6),
+        ("esf001.pfb.17", FileKind::Prefab, Game::SF6),
+        ("em0000.pfb.16", FileKind::Prefab, Game::DMC5),
+        ("stage.scn.20", FileKind::Scene, Game::SF6),
+        ("m01.scn.19", FileKind::Scene, Game::DMC5),
+    ];
+    for (file_name, kind, game) in files {
+        let file_type = detect(&[0; 16], file_name).unwrap();
+        assert_eq!((file_type.kind, file_type.profile().game), (kind, game), "{}", file_name);
+        assert_eq!(file_type.profile().file_version(kind), Some(file_type.version), "{}", file_name);
+    }
+}
+
+#[test]
+fn fchar_files_are_rejected_by_profiles_of_another_version() {
+    common::load_schema();
+    let written = write_fchar(&common::character(), &SF6);
+    assert!(parse_fchar(&written, &SF6).is_ok());
+    //DMC5 has no fchar files at all
+    assert!(parse_fchar(&written, &DMC5).is_err());
+}
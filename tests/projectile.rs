@@ -0,0 +1,69 @@
+mod common;
+
+use sf6_rsz_parser::fchar::projectile::projectiles_to_csv;
+use sf6_rsz_parser::fchar::{CharacterAsset, DataId};
+use sf6_rsz_parser::rsz::json_parser::TypeIDs;
+use sf6_rsz_parser::rsz::{Float2, Float4, RSZData, RSZValue};
+
+fn shot_key(projectile_id: i32) -> RSZData {
+    common::data("test.ShotKey", vec![common::field("ShotDataIndex", TypeIDs::S32, RSZValue::Int32(projectile_id))])
+}
+
+fn projectile_box(x: f32, y: f32, width: f32, height: f32) -> RSZData {
+    common::data("test.ProjectileBox", vec![
+        common::field("Rect", TypeIDs::Float4, RSZValue::Float4(Float4 { x, y, z: width, w: height })),
+    ])
+}
+
+//Projectile 3 is spawned by 5LP and 2MP, whose second key spawns nothing. Its boxes are entries 0 and 1 of the box table
+fn character_with_projectile() -> CharacterAsset {
+    let mut character = common::character();
+    character.action_list.push(common::action(600, "5LP", vec![(common::key_data(5, 6), shot_key(3))]));
+    character.action_list.push(common::action(601, "2MP", vec![
+        (common::key_data(10, 11), shot_key(3)),
+        (common::key_data(20, 21), shot_key(-1)),
+    ]));
+    character.data_id_table.push(DataId::ProjectileData);
+    character.data_list_table.push(common::data_list(vec![3], vec![(0x50, common::data("test.Projectile", vec![
+        common::field("Speed", TypeIDs::Float2, RSZValue::Float2(Float2 { x: 2.5, y: 0.0 })),
+        common::field("LifeTime", TypeIDs::S32, RSZValue::Int32(90)),
+        common::field("HitNum", TypeIDs::S32, RSZValue::Int32(1)),
+        common::field("BoxList", TypeIDs::S32, RSZValue::List(vec![RSZValue::Int32(0), RSZValue::Int32(1)])),
+    ]))]));
+    character.data_id_table.push(DataId::ProjectileBox);
+    character.data_list_table.push(common::data_list(vec![0, 1], vec![
+        (0x51, projectile_box(0.0, 10.0, 40.0, 20.0)),
+        (0x51, projectile_box(5.0, 10.0, 30.0, 10.0)),
+    ]));
+    character
+}
+
+#[test]
+fn shot_keys_point_at_projectile_entries() {
+    let character = character_with_projectile();
+    let keys = character.action_list[3].keys();
+    let (id, projectile) = character.key_projectile(keys[0].data).unwrap();
+    assert_eq!(id, 3);
+    assert_eq!(projectile.get_i64("LifeTime"), Some(90));
+    //negative indices mean the key spawns nothing
+    assert!(character.key_projectile(keys[1].data).is_none());
+}
+
+#[test]
+fn projectiles_list_their_boxes_and_the_actions_spawning_them() {
+    let character = character_with_projectile();
+    let projectiles = character.projectile_summaries();
+    assert_eq!(projectiles.len(), 1);
+    let projectile = &projectiles[0];
+    assert_eq!(projectile.id, 3);
+    assert_eq!(projectile.speed, vec![2.5, 0.0]);
+    assert_eq!((projectile.lifetime, projectile.hits), (Some(90), Some(1)));
+
+    let boxes: Vec<(u32, f64, f64)> = projectile.boxes.iter().map(|box_size| (box_size.id, box_size.width, box_size.height)).collect();
+    assert_eq!(boxes, vec![(0, 40.0, 20.0), (1, 30.0, 10.0)]);
+    let spawns: Vec<(i32, i32)> = projectile.spawned_by.iter().map(|spawn| (spawn.action_id, spawn.frame)).collect();
+    assert_eq!(spawns, vec![(600, 5), (601, 10)]);
+
+    let csv = projectiles_to_csv(&projectiles);
+    assert_eq!(csv.lines().nth(1), Some("3,2.5 0,90,1,\"40x20@(0, 10) 30x10@(5, 10)\",600@5 601@10"));
+}
@@ -0,0 +1,173 @@
+use sf6_rsz_parser::rsz::{parse_rsz, write_rsz};
+use sf6_rsz_parser::rsz::version::{rsz_layout, UserDataLayout, DMC5_VERSION, MHRISE_VERSION, RE2_VERSION, RE3_VERSION, RE4_VERSION, RE8_VERSION, SF6_VERSION};
+
+const RSZ_MAGIC: &[u8; 4] = b"RSZ\0";
+
+enum FixtureUserData<'a> {
+    Path(&'a str),
+    Embedded(Vec<u8>),
+}
+
+fn align_to_16(bytes: &mut Vec<u8>) {
+    let alignment = (16 - bytes.len() % 16) % 16;
+    bytes.resize(bytes.len() + alignment, 0);
+}
+
+fn put_u32(bytes: &mut [u8], offset: usize, value: u32) {
+    bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(bytes: &mut [u8], offset: usize, value: u64) {
+    bytes[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+}
+
+//Builds an RSZ block by hand where every instance but the null one is userdata, so no schema is needed to read it
+fn fixture(version: u32, userdata: &[FixtureUserData]) -> Vec<u8> {
+    let has_userdata = rsz_layout(version).userdata != UserDataLayout::None;
+    let header_size = if has_userdata { 48 } else { 32 };
+    let object_count = userdata.len().min(1);
+    let mut bytes: Vec<u8> = vec![0; header_size];
+    if object_count == 1 {
+        bytes.extend_from_slice(&1i32.to_le_bytes());
+    }
+    let instance_offsets = bytes.len();
+    bytes.extend_from_slice(&[0; 8]);
+    for n in 1..=userdata.len() as u32 {
+        bytes.extend_from_slice(&(0x1000 + n).to_le_bytes());
+        bytes.extend_from_slice(&(0x2000 + n).to_le_bytes());
+    }
+    align_to_16(&mut bytes);
+    let userdata_offset = bytes.len();
+    let embedded = userdata.iter().any(|userdata| matches!(userdata, FixtureUserData::Embedded(_)));
+    let entry_size = if embedded { 24 } else { 16 };
+    bytes.resize(userdata_offset + entry_size * userdata.len(), 0);
+    for (n, userdata) in userdata.iter().enumerate() {
+        let entry = userdata_offset + entry_size * n;
+        put_u32(&mut bytes, entry, n as u32 + 1);
+        put_u32(&mut bytes, entry + 4, 0x1000 + n as u32 + 1);
+        match userdata {
+            FixtureUserData::Path(path) => {
+                let str_offset = bytes.len() as u64;
+                for char in path.encode_utf16() {
+                    bytes.extend_from_slice(&char.to_le_bytes());
+                }
+                bytes.extend_from_slice(&[0; 2]);
+                put_u64(&mut bytes, entry + 8, str_offset);
+            }
+            FixtureUserData::Embedded(nested) => {
+                align_to_16(&mut bytes);
+                let rsz_offset = bytes.len() as u64;
+                bytes.extend_from_slice(nested);
+                put_u32(&mut bytes, entry + 8, 0xABCD);
+                put_u32(&mut bytes, entry + 12, nested.len() as u32);
+                put_u64(&mut bytes, entry + 16, rsz_offset);
+            }
+        }
+    }
+    align_to_16(&mut bytes);
+    let data_offset = bytes.len();
+
+    bytes[0..4].copy_from_slice(RSZ_MAGIC);
+    put_u32(&mut bytes, 4, version);
+    put_u32(&mut bytes, 8, object_count as u32);
+    put_u32(&mut bytes, 12, userdata.len() as u32 + 1);
+    match has_userdata {
+        true => {
+            put_u32(&mut bytes, 16, userdata.len() as u32);
+            put_u64(&mut bytes, 24, instance_offsets as u64);
+            put_u64(&mut bytes, 32, data_offset as u64);
+            put_u64(&mut bytes, 40, userdata_offset as u64);
+        }
+        false => {
+            put_u64(&mut bytes, 16, instance_offsets as u64);
+            put_u64(&mut bytes, 24, data_offset as u64);
+        }
+    }
+    bytes
+}
+
+fn assert_round_trip(fixture: &[u8]) {
+    let (_, rsz) = parse_rsz(fixture, 0).unwrap();
+    let mut written: Vec<u8> = vec![];
+    write_rsz(&rsz, &mut written);
+    assert_eq!(written, fixture);
+}
+
+fn assert_path_userdata(version: u32) {
+    let bytes = fixture(version, &[FixtureUserData::Path("battle/param.user"), FixtureUserData::Path("battle/rule.user")]);
+    let (_, rsz) = parse_rsz(&bytes, 0).unwrap();
+    assert_eq!(rsz.header.version, version);
+    assert_eq!(rsz.object_table, vec![1]);
+    assert_eq!(rsz.instance_infos.len(), 3);
+    assert_eq!(rsz.instance_infos[2].hash, 0x1002);
+    assert_eq!(rsz.instance_infos[2].crc, 0x2002);
+    let paths: Vec<&str> = rsz.userdata_infos.iter().map(|userdata| userdata.string.as_str()).collect();
+    assert_eq!(paths, vec!["battle/param.user", "battle/rule.user"]);
+    assert!(rsz.userdata_infos.iter().all(|userdata| userdata.rsz.is_none()));
+    assert_round_trip(&bytes);
+}
+
+fn assert_embedded_userdata(version: u32) {
+    let nested = fixture(version, &[FixtureUserData::Path("nested.user")]);
+    let bytes = fixture(version, &[FixtureUserData::Embedded(nested)]);
+    let (_, rsz) = parse_rsz(&bytes, 0).unwrap();
+    let userdata = &rsz.userdata_infos[0];
+    assert_eq!(userdata.json_path_hash, Some(0xABCD));
+    let nested_rsz = rsz.userdata_rsz(1).expect("embedded userdata should hold an RSZ block");
+    assert_eq!(nested_rsz.userdata_infos[0].string, "nested.user");
+    assert_round_trip(&bytes);
+}
+
+#[test]
+fn dmc5_layout() {
+    assert_eq!(rsz_layout(DMC5_VERSION).userdata, UserDataLayout::Path);
+    assert_path_userdata(DMC5_VERSION);
+}
+
+#[test]
+fn re2_layout() {
+    assert_eq!(rsz_layout(RE2_VERSION).userdata, UserDataLayout::Path);
+    assert_path_userdata(RE2_VERSION);
+}
+
+#[test]
+fn re3_layout() {
+    assert_eq!(rsz_layout(RE3_VERSION).userdata, UserDataLayout::Path);
+    assert_path_userdata(RE3_VERSION);
+}
+
+#[test]
+fn re8_layout() {
+    assert_eq!(rsz_layout(RE8_VERSION).userdata, UserDataLayout::Path);
+    assert_path_userdata(RE8_VERSION);
+}
+
+#[test]
+fn mhrise_layout() {
+    assert_eq!(rsz_layout(MHRISE_VERSION).userdata, UserDataLayout::Path);
+    assert_path_userdata(MHRISE_VERSION);
+}
+
+#[test]
+fn sf6_layout() {
+    assert_eq!(rsz_layout(SF6_VERSION).userdata, UserDataLayout::Embeddable);
+    assert_path_userdata(SF6_VERSION);
+    assert_embedded_userdata(SF6_VERSION);
+}
+
+#[test]
+fn re4_layout() {
+    assert_eq!(rsz_layout(RE4_VERSION).userdata, UserDataLayout::Embeddable);
+    assert_path_userdata(RE4_VERSION);
+    assert_embedded_userdata(RE4_VERSION);
+}
+
+#[test]
+fn header_without_userdata() {
+    let bytes = fixture(3, &[]);
+    assert_eq!(rsz_layout(3).header_size, 32);
+    let (_, rsz) = parse_rsz(&bytes, 0).unwrap();
+    assert_eq!(rsz.header.userdata_count, 0);
+    assert_eq!(rsz.instance_infos.len(), 1);
+    assert_round_trip(&bytes);
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use common::TARGET_CLASS;
+use sf6_rsz_parser::detect::SCN_MAGIC;
+use sf6_rsz_parser::profile::{DMC5, SF6};
+use sf6_rsz_parser::rsz::json_parser::TypeIDs;
+use sf6_rsz_parser::rsz::{FolderInfo, ResourceInfo, RSZValue, StandardGameObjectInfo, UserDataInfo, GUID};
+use sf6_rsz_parser::scene::{parse_scene, write_scene, PrefabInfo, Scene, SceneHeader};
+
+fn gameobject_info(id: i32, parent_id: i32, prefab_id: i32) -> StandardGameObjectInfo {
+    StandardGameObjectInfo {
+        guid: serde_json::from_str::<GUID>(&format!("{{\"uuid\": {:?}}}", [id as u8 + 1; 16])).unwrap(),
+        id,
+        parent_id,
+        component_count: 0,
+        unknown: 0,
+        prefab_id,
+    }
+}
+
+fn resource(path: &str) -> ResourceInfo {
+    ResourceInfo {
+        str_offset: None,
+        string: path.to_string(),
+    }
+}
+
+//GameObject 0 is spawned from the only prefab and sits in folder 2, GameObject 1 is placed directly
+fn scene() -> Scene {
+    let target = |hp: i32| (TARGET_CLASS, common::data("test.Target", vec![common::field("Hp", TypeIDs::S32, RSZValue::Int32(hp))]));
+    Scene {
+        header: SceneHeader {
+            magic: SCN_MAGIC,
+            info_count: 2,
+            resource_count: 2,
+            folder_count: 1,
+            prefab_count: 1,
+            userdata_count: 1,
+            folder_tbl: 0,
+            resource_info_tbl: 0,
+            prefab_info_tbl: 0,
+            userdata_info_tbl: 0,
+            data_offset: 0,
+        },
+        gameobject_infos: vec![gameobject_info(0, 2, 0), gameobject_info(1, -1, -1)],
+        folder_infos: vec![FolderInfo {
+            id: 2,
+            parent_id: -1,
+        }],
+        resource_infos: vec![resource("test/a.mesh"), resource("test/longer/b.mdf2")],
+        prefab_infos: vec![PrefabInfo {
+            str_offset: 0,
+            parent_id: 0,
+            string: "test/enemy.pfb".to_string(),
+        }],
+        userdata_infos: vec![UserDataInfo {
+            instance_id: 0,
+            type_id: 0,
+            str_offset: 0,
+            string: "test/param.user".to_string(),
+            json_path_hash: None,
+            rsz: None,
+        }],
+        gameobject: common::rsz(vec![target(100), target(200), target(300)]),
+    }
+}
+
+#[test]
+fn scenes_survive_a_round_trip() {
+    common::load_schema();
+    for profile in [&SF6, &DMC5] {
+        let written = write_scene(&scene(), profile);
+        let (_, parsed) = parse_scene(&written, profile).unwrap();
+        let paths: Vec<&str> = parsed.resource_infos.iter().map(|resource| resource.string.as_str()).collect();
+        assert_eq!(paths, vec!["test/a.mesh", "test/longer/b.mdf2"]);
+        assert_eq!(parsed.prefab_infos[0].string, "test/enemy.pfb");
+        assert_eq!(parsed.userdata_infos[0].string, "test/param.user");
+        assert_eq!(parsed.gameobject_infos[0].prefab_id, 0);
+        assert_eq!(write_scene(&parsed, profile), written);
+    }
+}
+
+#[test]
+fn gameobjects_are_linked_to_their_prefab() {
+    let scene = scene();
+    assert_eq!(scene.gameobject_prefab(&scene.gameobject_infos[0]).unwrap().string, "test/enemy.pfb");
+    assert!(scene.gameobject_prefab(&scene.gameobject_infos[1]).is_none());
+
+    let tree = scene.tree();
+    let roots: Vec<(i32, Option<&str>)> = tree.roots.iter().map(|node| (node.id, node.prefab)).collect();
+    assert_eq!(roots, vec![(1, None), (2, None)]);
+    let spawned = &tree.roots[1].children[0];
+    assert_eq!((spawned.id, spawned.prefab), (0, Some("test/enemy.pfb")));
+    assert_eq!(spawned.gameobject.unwrap().get_i64("Hp"), Some(100));
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use sf6_rsz_parser::profile::{GameProfile, SF6};
+use sf6_rsz_parser::rsz::json_parser::get_rsz_class_name;
+use sf6_rsz_parser::schema::{load_schema_bytes, load_schema_file, SchemaSource};
+
+fn temp_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("sf6_rsz_parser_{}_{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+//Each test loads into the same global schema, so they run as one to keep the order fixed
+#[test]
+fn schemas_load_from_files_and_bytes() {
+    let path = temp_file("rszsf6.json", common::SCHEMA.as_bytes());
+    let loaded = load_schema_file(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.unwrap(), SchemaSource::File(path));
+    assert_eq!(get_rsz_class_name(&common::ACTION_CLASS).unwrap(), "test.ActionInfo");
+
+    let missing = std::env::temp_dir().join(format!("sf6_rsz_parser_{}_missing.json", std::process::id()));
+    let error = load_schema_file(&missing).unwrap_err();
+    assert!(error.to_string().starts_with("Schema error: could not read"), "{}", error);
+
+    let path = temp_file("broken.json", b"{\"10\": ");
+    let broken = load_schema_file(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(broken.is_err());
+    //a failed load keeps the schema loaded before it
+    assert_eq!(get_rsz_class_name(&common::ACTION_CLASS).unwrap(), "test.ActionInfo");
+
+    assert_eq!(load_schema_bytes(&SF6, common::SCHEMA.as_bytes().to_vec()).unwrap(), SchemaSource::Embedded("rszsf6.json"));
+    let no_dump = GameProfile {
+        schema: None,
+        ..SF6
+    };
+    let error = load_schema_bytes(&no_dump, common::SCHEMA.as_bytes().to_vec()).unwrap_err();
+    assert_eq!(error.to_string(), "Schema error: no schema dump is known for SF6");
+}
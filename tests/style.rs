@@ -0,0 +1,69 @@
+mod common;
+
+use sf6_rsz_parser::fchar::{CharacterAsset, StyleData};
+
+//Style 1 changes the gravity of the default style, style 2 keeps style 1's gravity and only changes the jump frames
+fn character_with_style_chain() -> CharacterAsset {
+    let mut character = common::character();
+    character.id_table = vec![0, 1, 2];
+    character.parent_id_table = vec![-1, 0, 1];
+    character.style_data.push(StyleData {
+        data_start_offset: 0,
+        rsz_offset: 0,
+        data_end_offset: 0,
+        rsz: common::rsz(vec![(common::STYLE_CLASS, common::style_data(0.75, 50))]),
+    });
+    character.header.style_count = 3;
+    character
+}
+
+#[test]
+fn styles_are_layered_over_their_parents() {
+    let character = character_with_style_chain();
+    let effective = character.effective_style(2).unwrap();
+    assert_eq!(effective.chain, vec![2, 1, 0]);
+    assert_eq!(effective.data[0].get_f64("Gravity"), Some(0.75));
+    assert_eq!(effective.data[0].get_i64("JumpFrame"), Some(50));
+
+    let parent = character.effective_style(1).unwrap();
+    assert_eq!(parent.data[0].get_f64("Gravity"), Some(0.75));
+    assert_eq!(parent.data[0].get_i64("JumpFrame"), Some(40));
+}
+
+#[test]
+fn style_deltas_only_list_the_fields_a_style_sets() {
+    let character = character_with_style_chain();
+    let delta = character.style_delta(2).unwrap();
+    assert_eq!(delta.parent_id, Some(1));
+    let fields: Vec<&str> = delta.overrides.iter().map(|change| change.field.as_str()).collect();
+    assert_eq!(fields, vec!["JumpFrame"]);
+}
+
+#[test]
+fn grandchildren_can_reset_a_field_to_the_default() {
+    let mut character = character_with_style_chain();
+    character.style_data[1].rsz = common::rsz(vec![(common::STYLE_CLASS, common::style_data(0.5, 50))]);
+    let effective = character.effective_style(2).unwrap();
+    assert_eq!(effective.data[0].get_f64("Gravity"), Some(0.5));
+    assert_eq!(effective.data[0].get_i64("JumpFrame"), Some(50));
+
+    let delta = character.style_delta(2).unwrap();
+    let fields: Vec<(&str, Option<f64>, Option<f64>)> = delta.overrides.iter()
+        .map(|change| (change.field.as_str(), change.inherited.as_ref().and_then(|value| value.as_f64()), change.value.as_f64()))
+        .collect();
+    assert_eq!(fields, vec![("Gravity", Some(0.75), Some(0.5)), ("JumpFrame", Some(40.0), Some(50.0))]);
+}
+
+#[test]
+fn style_tree_nests_children_and_lists_cycles_at_the_top() {
+    let mut character = character_with_style_chain();
+    let tree = character.style_tree();
+    assert_eq!((tree.len(), tree[0].id, tree[0].children[0].id, tree[0].children[0].children[0].id), (1, 0, 1, 2));
+
+    //style 1 and 2 name each other as parent
+    character.parent_id_table = vec![-1, 2, 1];
+    let tree = character.style_tree();
+    let roots: Vec<(i32, usize)> = tree.iter().map(|node| (node.id, node.children.len())).collect();
+    assert_eq!(roots, vec![(0, 0), (1, 1)]);
+    assert_eq!(tree[1].children[0].id, 2);
+}
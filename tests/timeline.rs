@@ -0,0 +1,23 @@
+mod common;
+
+use sf6_rsz_parser::fchar::DataId;
+
+#[test]
+fn keys_are_not_paired_when_the_key_and_root_counts_differ() {
+    let mut character = common::character();
+    assert_eq!(character.action_list[0].keys().len(), 1);
+    character.action_list[0].objects[0].info.object_data.key_data.push(common::key_data(20, 30));
+    assert!(character.action_list[0].keys().is_empty());
+    let timeline = character.action_list[0].timeline();
+    assert_eq!(timeline.len(), 2);
+    assert_eq!(timeline[0].key_class, "");
+}
+
+#[test]
+fn data_entries_are_not_paired_when_the_id_and_root_counts_differ() {
+    let mut character = common::character();
+    let attacks = character.data_table_mut(DataId::AttackDataParams).unwrap();
+    assert!(attacks.entry(1).is_some());
+    attacks.data_ids.push(2);
+    assert!(attacks.entry(1).is_none());
+}
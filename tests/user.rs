@@ -0,0 +1,73 @@
+mod common;
+
+use common::FIXED_CLASS;
+use sf6_rsz_parser::detect::USR_MAGIC;
+use sf6_rsz_parser::profile::{DMC5, SF6};
+use sf6_rsz_parser::rsz::json_parser::TypeIDs;
+use sf6_rsz_parser::rsz::{Int3, ResourceInfo, RSZValue};
+use sf6_rsz_parser::user::{parse_user, write_user, UserData, UserDataHeader};
+
+fn resource(path: &str) -> ResourceInfo {
+    ResourceInfo {
+        str_offset: None,
+        string: path.to_string(),
+    }
+}
+
+//An Sfix field holding -1.5 and an Sfix3 whose last component is the smallest step, the paths have different lengths so inline paths can't be read with a fixed stride
+fn user_data() -> UserData {
+    UserData {
+        header: UserDataHeader {
+            magic: USR_MAGIC,
+            resource_count: 2,
+            userdata_count: 0,
+            info_count: 1,
+            resource_info_tbl: 0,
+            userdata_info_tbl: 0,
+            data_offset: 0,
+        },
+        resource_infos: vec![resource("test/a.mesh"), resource("test/longer/b.mdf2")],
+        userdata_infos: vec![],
+        rsz: common::rsz(vec![(FIXED_CLASS, common::data("test.Fixed", vec![
+            common::field("Offset", TypeIDs::Sfix, RSZValue::Fixed(-98304)),
+            common::field("Position", TypeIDs::Sfix3, RSZValue::Fixed3(Int3 { x: 65536, y: -16384, z: 1 })),
+        ]))]),
+    }
+}
+
+#[test]
+fn user_files_survive_a_round_trip() {
+    common::load_schema();
+    for profile in [&SF6, &DMC5] {
+        let written = write_user(&user_data(), profile);
+        let (_, parsed) = parse_user(&written, profile).unwrap();
+        let paths: Vec<&str> = parsed.resource_infos.iter().map(|resource| resource.string.as_str()).collect();
+        assert_eq!(paths, vec!["test/a.mesh", "test/longer/b.mdf2"]);
+        assert!(parsed.rsz.data[0].fields[0].value == RSZValue::Fixed(-98304));
+        assert_eq!(write_user(&parsed, profile), written);
+    }
+}
+
+#[test]
+fn fixed_point_values_keep_their_sign() {
+    common::load_schema();
+    let mut user = user_data();
+    assert_eq!(user.rsz.data[0].get_f64("Offset"), Some(-1.5));
+    assert!(user.rsz.data[0].set_f64("Offset", -2.25));
+    let (_, parsed) = parse_user(&write_user(&user, &SF6), &SF6).unwrap();
+    assert_eq!(parsed.rsz.data[0].get_f64("Offset"), Some(-2.25));
+    assert!(parsed.rsz.data[0].fields[0].value == RSZValue::Fixed(-147456));
+}
+
+#[test]
+fn fixed_point_vectors_keep_their_raw_components() {
+    common::load_schema();
+    let mut user = user_data();
+    assert_eq!(user.rsz.data[0].fields[1].value.components(), Some(vec![1.0, -0.25, 1.0 / 65536.0]));
+    let (_, parsed) = parse_user(&write_user(&user, &SF6), &SF6).unwrap();
+    assert!(parsed.rsz.data[0].fields[1].value == RSZValue::Fixed3(Int3 { x: 65536, y: -16384, z: 1 }));
+
+    assert!(user.rsz.data[0].fields[1].value.set_components(&[-1.5, 0.5, 0.0]));
+    let (_, parsed) = parse_user(&write_user(&user, &SF6), &SF6).unwrap();
+    assert!(parsed.rsz.data[0].fields[1].value == RSZValue::Fixed3(Int3 { x: -98304, y: 32768, z: 0 }));
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use sf6_rsz_parser::fchar::{CharacterAsset, DataId};
+use sf6_rsz_parser::rsz::json_parser::TypeIDs;
+use sf6_rsz_parser::rsz::{RSZData, RSZValue};
+
+fn voice_facial(voice_id: i32, facial_id: i32) -> RSZData {
+    common::data("test.VoiceFacial", vec![
+        common::field("VoiceID", TypeIDs::S32, RSZValue::Int32(voice_id)),
+        common::field("FacialID", TypeIDs::S32, RSZValue::Int32(facial_id)),
+    ])
+}
+
+//Two more action list entries reusing id 600, each with a voice key, the first 600 entry has none
+fn character_with_voices_in_repeated_id() -> CharacterAsset {
+    let mut character = common::character();
+    for (frame, entry_id) in [(8, 1), (2, 0)] {
+        let voice_key = common::data("test.VoiceKey", vec![common::field("VoiceFacialDataIndex", TypeIDs::S32, RSZValue::Int32(entry_id))]);
+        character.action_list.push(common::action(600, "5LP", vec![(common::key_data(frame, frame + 1), voice_key)]));
+    }
+    character.data_id_table.push(DataId::VoiceFacialData);
+    character.data_list_table.push(common::data_list(vec![0, 1], vec![(0x60, voice_facial(100, 10)), (0x60, voice_facial(101, 11))]));
+    character.data_id_table.push(DataId::VoiceFacialDataEN);
+    character.data_list_table.push(common::data_list(vec![0], vec![(0x60, voice_facial(200, 10))]));
+    character
+}
+
+#[test]
+fn every_action_list_entry_is_visited_for_voices() {
+    let voice_facials = character_with_voices_in_repeated_id().action_voice_facials(600);
+    let rows: Vec<(DataId, u32, Option<i64>, i32)> = voice_facials.iter()
+        .map(|voice_facial| (voice_facial.table, voice_facial.entry_id, voice_facial.voice_id, voice_facial.frame))
+        .collect();
+    assert_eq!(rows, vec![
+        (DataId::VoiceFacialData, 1, Some(101), 8),
+        (DataId::VoiceFacialData, 0, Some(100), 2),
+        (DataId::VoiceFacialDataEN, 0, Some(200), 2),
+    ]);
+}